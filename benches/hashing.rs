@@ -0,0 +1,114 @@
+//! The parts whose inner loops hash small integer tuples, on generated inputs.
+//!
+//! Run with `cargo bench --bench hashing`.
+
+use std::error::Error;
+
+use advent_of_code_2023::*;
+use aoc_runner::{ArcStr, Runner};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+type MakeRunner = fn(ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>>;
+
+/// Small deterministic PRNG, good enough for generating grids
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+}
+
+fn grid(seed: u64, side: usize, cell: impl Fn(u64) -> char) -> String {
+    let mut rng = Lcg(seed);
+    (0..side)
+        .map(|_| (0..side).map(|_| cell(rng.next())).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Numbers and gears scattered over a 140x140 schematic
+fn day3_input() -> String {
+    grid(3, 140, |r| match r % 10 {
+        0..=2 => char::from(b'0' + (r / 10 % 10) as u8),
+        3 => '*',
+        _ => '.',
+    })
+}
+
+/// A loop snaking through every column of a 140x140 map: along the top row from `S`, then down
+/// and up the columns from right to left
+fn day10_input() -> String {
+    let side = 140;
+    let mut path = (0..side).map(|x| (x, 0)).collect::<Vec<_>>();
+    for (i, x) in (0..side).rev().enumerate() {
+        if i % 2 == 0 {
+            path.extend((1..side).map(|y| (x, y)));
+        } else {
+            path.extend((1..side).rev().map(|y| (x, y)));
+        }
+    }
+
+    let mut map = vec![vec!['.'; side as usize]; side as usize];
+    for (i, &(x, y)) in path.iter().enumerate() {
+        let prev = path[(i + path.len() - 1) % path.len()];
+        let next = path[(i + 1) % path.len()];
+        let dirs = [prev, next].map(|(px, py)| (px - x, py - y));
+        let has = |d| dirs.contains(&d);
+        map[y as usize][x as usize] = match (has((0, -1)), has((0, 1)), has((1, 0))) {
+            _ if i == 0 => 'S',
+            (true, true, _) => '|',
+            (true, _, true) => 'L',
+            (true, _, _) => 'J',
+            (_, true, true) => 'F',
+            (_, true, _) => '7',
+            _ => '-',
+        };
+    }
+    map.into_iter()
+        .map(String::from_iter)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Rocks on a 100x100 platform
+fn day14_input() -> String {
+    grid(14, 100, |r| match r % 10 {
+        0..=1 => 'O',
+        2 => '#',
+        _ => '.',
+    })
+}
+
+/// Mirrors and splitters on a 110x110 contraption
+fn day16_input() -> String {
+    grid(16, 110, |r| match r % 40 {
+        0 => '/',
+        1 => '\\',
+        2 => '|',
+        3 => '-',
+        _ => '.',
+    })
+}
+
+fn bench_part(c: &mut Criterion, name: &str, factory: MakeRunner, input: &str) {
+    let runner = factory(ArcStr::from(input)).unwrap();
+    c.bench_function(name, |b| b.iter(|| black_box(runner.run())));
+}
+
+fn benches(c: &mut Criterion) {
+    bench_part(c, "day3_part2", Factory::day3_part2, &day3_input());
+    let day10 = day10_input();
+    bench_part(c, "day10_part1", Factory::day10_part1, &day10);
+    bench_part(c, "day10_part2", Factory::day10_part2, &day10);
+    bench_part(c, "day14_part2", Factory::day14_part2, &day14_input());
+    let day16 = day16_input();
+    bench_part(c, "day16_part2", Factory::day16_part2, &day16);
+}
+
+criterion_group!(hashing_benches, benches);
+criterion_main!(hashing_benches);
@@ -0,0 +1,136 @@
+//! Serial vs. parallel timings of the parts that use `parallel::map`, on generated inputs.
+//!
+//! Run with `cargo bench --features parallel --bench parallel`.
+
+use std::error::Error;
+
+use advent_of_code_2023::{parallel, *};
+use aoc_runner::{ArcStr, Runner};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+type MakeRunner = fn(ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>>;
+
+/// Small deterministic PRNG, good enough for generating grids
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+}
+
+fn day5_input() -> String {
+    let maps = "seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+    let mut rng = Lcg(5);
+    let seeds = (0..20_000)
+        .map(|_| (rng.next() % 100).to_string())
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!("seeds: {seeds}\n\n{maps}")
+}
+
+fn day12_input() -> String {
+    "???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1\n"
+        .repeat(100)
+}
+
+fn day13_input() -> String {
+    ["#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.
+
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#"; 200]
+        .join("\n\n")
+}
+
+fn day16_input() -> String {
+    let mut rng = Lcg(16);
+    (0..110)
+        .map(|_| {
+            (0..110)
+                .map(|_| match rng.next() % 40 {
+                    0 => '/',
+                    1 => '\\',
+                    2 => '|',
+                    3 => '-',
+                    _ => '.',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn bench_part(c: &mut Criterion, name: &str, factory: MakeRunner, input: String) {
+    let runner = factory(ArcStr::from(&input)).unwrap();
+    let mut group = c.benchmark_group(name);
+
+    parallel::set_threads(1);
+    group.bench_function("serial", |b| b.iter(|| black_box(runner.run())));
+
+    parallel::set_threads(0);
+    group.bench_function(format!("parallel/{}", parallel::threads()), |b| {
+        b.iter(|| black_box(runner.run()))
+    });
+
+    group.finish();
+}
+
+fn benches(c: &mut Criterion) {
+    bench_part(c, "day5_part1", Factory::day5_part1, day5_input());
+    bench_part(c, "day12_part2", Factory::day12_part2, day12_input());
+    bench_part(c, "day13_part2", Factory::day13_part2, day13_input());
+    bench_part(c, "day16_part2", Factory::day16_part2, day16_input());
+}
+
+criterion_group!(parallel_benches, benches);
+criterion_main!(parallel_benches);
@@ -0,0 +1,152 @@
+//! Parsing and solving, together and apart, on generated inputs big enough for the parsing to
+//! show.
+//!
+//! Run with `cargo bench --bench parsing`.
+
+use std::time::Duration;
+
+use advent_of_code_2023::registry::{self, Answer};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+/// Small deterministic PRNG, good enough for generating inputs
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+}
+
+/// Name of the `i`th node, `AAA` first
+fn node(i: usize) -> String {
+    (0..3)
+        .rev()
+        .map(|d| (b'A' + (i / 26usize.pow(d) % 26) as u8) as char)
+        .collect()
+}
+
+/// Lines of letters with digits and spelled out digits mixed in
+fn day1_input() -> String {
+    const WORDS: &[&str] = &[
+        "one", "two", "three", "four", "five", "six", "seven", "eight",
+    ];
+    let mut rng = Lcg(1);
+    (0..20_000)
+        .map(|_| {
+            let mut line = String::new();
+            for _ in 0..8 {
+                match rng.next() % 4 {
+                    0 => line.push(char::from(b'1' + (rng.next() % 9) as u8)),
+                    1 => line += WORDS[rng.next() as usize % WORDS.len()],
+                    _ => line.push(char::from(b'a' + (rng.next() % 26) as u8)),
+                }
+            }
+            // Every line needs a digit
+            line + "7"
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Games of a few draws of up to 15 cubes of each color
+fn day2_input() -> String {
+    let mut rng = Lcg(2);
+    (1..=20_000)
+        .map(|id| {
+            let sets = (0..4)
+                .map(|_| {
+                    ["red", "green", "blue"]
+                        .map(|c| format!("{} {c}", rng.next() % 15 + 1))
+                        .join(", ")
+                })
+                .collect::<Vec<_>>();
+            format!("Game {id}: {}", sets.join("; "))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Cards with 5 winning numbers and 8 numbers each, rarely matching so the copies of part 2 stay
+/// small
+fn day4_input() -> String {
+    let mut rng = Lcg(4);
+    let mut numbers = |n| {
+        (0..n)
+            .map(|_| format!("{:2}", rng.next() % 99 + 1))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    (1..=20_000)
+        .map(|id| format!("Card {id:5}: {} | {}", numbers(5), numbers(8)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Every three letter node in a ring from `AAA` to `ZZZ`, each pointing to the next one on both
+/// sides
+fn day8_input() -> String {
+    let count = 26 * 26 * 26;
+    let nodes = (0..count)
+        .map(|i| format!("{} = ({1}, {1})", node(i), node((i + 1).min(count - 1))))
+        .collect::<Vec<_>>();
+    format!("LRRL\n\n{}", nodes.join("\n"))
+}
+
+/// Steps on labels of up to six letters
+fn day15_input() -> String {
+    (0..100_000)
+        .map(|i: usize| {
+            let label = node(i * 7919 % 17_576).to_lowercase() + &node(i % 5).to_lowercase();
+            match i % 3 {
+                0 => format!("{label}-"),
+                _ => format!("{label}={}", i % 9 + 1),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// The whole solution, and its generator and part on their own as
+/// [`registry::Solution::solve`] times them
+fn bench_part(c: &mut Criterion, day: u32, part: u32, input: &str) {
+    let solution = registry::find(day, part, None).unwrap();
+    let name = format!("day{day}_part{part}");
+    c.bench_function(&name, |b| {
+        b.iter(|| black_box(solution.solve(input).unwrap()))
+    });
+
+    let mut bench = |phase, time: fn(&Answer) -> Duration| {
+        c.bench_function(&format!("{name}_{phase}"), |b| {
+            b.iter_custom(|iters| {
+                (0..iters)
+                    .map(|_| time(&solution.solve(input).unwrap()))
+                    .sum()
+            })
+        });
+    };
+    bench("generator", |a| a.generator);
+    bench("runner", |a| a.runner);
+}
+
+fn benches(c: &mut Criterion) {
+    let day1 = day1_input();
+    bench_part(c, 1, 1, &day1);
+    bench_part(c, 1, 2, &day1);
+    let day2 = day2_input();
+    bench_part(c, 2, 1, &day2);
+    bench_part(c, 2, 2, &day2);
+    let day4 = day4_input();
+    bench_part(c, 4, 1, &day4);
+    bench_part(c, 4, 2, &day4);
+    bench_part(c, 8, 1, &day8_input());
+    let day15 = day15_input();
+    bench_part(c, 15, 1, &day15);
+    bench_part(c, 15, 2, &day15);
+}
+
+criterion_group!(parsing_benches, benches);
+criterion_main!(parsing_benches);
@@ -0,0 +1,349 @@
+use std::{
+    error::Error,
+    fmt, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+use regex::Regex;
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+
+/// Directory downloaded inputs are cached in. Same layout `cargo aoc` uses.
+pub const INPUT_DIR: &str = "input";
+
+/// Automated requests must identify themselves, see the note at the bottom of adventofcode.com/about
+pub const USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/ndomvi/advent_of_code_2023)"
+);
+
+/// Client for the adventofcode.com website
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(session: impl Into<String>) -> Self {
+        Self {
+            base_url: BASE_URL.to_owned(),
+            session: session.into(),
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+        }
+    }
+
+    /// Points the client at a different server, e.g. a local mock
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_owned();
+        self
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        self.agent
+            .request(method, &format!("{}{path}", self.base_url))
+            .set("Cookie", &format!("session={}", self.session))
+    }
+
+    fn get(&self, path: &str) -> Result<String, Box<dyn Error>> {
+        read_response(self.request("GET", path).call(), "GET", path)
+    }
+
+    /// Fetches the puzzle input. Always hits the server, see [`Client::download_input`].
+    pub fn fetch_input(&self, year: u32, day: u32) -> Result<String, Box<dyn Error>> {
+        self.get(&format!("/{year}/day/{day}/input"))
+    }
+
+    /// Returns the path of the cached input, downloading it first if it is not cached yet.
+    /// A cached input is never fetched again.
+    pub fn download_input(
+        &self,
+        cache_dir: impl AsRef<Path>,
+        year: u32,
+        day: u32,
+    ) -> Result<PathBuf, Box<dyn Error>> {
+        let path = input_path(cache_dir, year, day);
+        if path.exists() {
+            return Ok(path);
+        }
+
+        let input = self.fetch_input(year, day)?;
+        fs::create_dir_all(path.parent().expect("Input path always has a parent"))?;
+        // Write to a temporary file first, so an interrupted download is not mistaken for a cached one
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, input)?;
+        fs::rename(&tmp, &path)?;
+
+        Ok(path)
+    }
+
+    /// Posts an answer and parses the resulting page. Does not check the submission history.
+    pub fn submit_answer(
+        &self,
+        year: u32,
+        day: u32,
+        part: u32,
+        answer: &str,
+    ) -> Result<Submission, Box<dyn Error>> {
+        let path = format!("/{year}/day/{day}/answer");
+        let page = read_response(
+            self.request("POST", &path)
+                .send_form(&[("level", &part.to_string()), ("answer", answer)]),
+            "POST",
+            &path,
+        )?;
+
+        Submission::parse(&page)
+    }
+}
+
+fn read_response(
+    resp: Result<ureq::Response, ureq::Error>,
+    method: &str,
+    path: &str,
+) -> Result<String, Box<dyn Error>> {
+    match resp {
+        Ok(resp) => Ok(resp.into_string()?),
+        Err(ureq::Error::Status(code, resp)) => Err(format!(
+            "{method} {path} failed with {code}: {}",
+            resp.into_string().unwrap_or_default().trim()
+        )
+        .into()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Incorrect,
+    TooHigh,
+    TooLow,
+    /// Submitted too soon after the previous answer, the answer was not checked
+    RateLimited,
+    /// The part was already solved (or is not unlocked yet), the answer was not checked
+    WrongLevel,
+}
+
+impl Verdict {
+    /// Whether the server actually checked the answer
+    pub fn is_checked(self) -> bool {
+        !matches!(self, Self::RateLimited | Self::WrongLevel)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Correct => "correct",
+            Self::Incorrect => "incorrect",
+            Self::TooHigh => "too-high",
+            Self::TooLow => "too-low",
+            Self::RateLimited => "rate-limited",
+            Self::WrongLevel => "wrong-level",
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "correct" => Self::Correct,
+            "incorrect" => Self::Incorrect,
+            "too-high" => Self::TooHigh,
+            "too-low" => Self::TooLow,
+            "rate-limited" => Self::RateLimited,
+            "wrong-level" => Self::WrongLevel,
+            _ => return Err(format!("Unknown verdict: {s}")),
+        })
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Result of posting an answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    pub verdict: Verdict,
+    /// How long the site wants us to wait before the next submission
+    pub wait: Option<Duration>,
+}
+
+impl Submission {
+    /// Parses the page returned after posting an answer
+    pub fn parse(page: &str) -> Result<Self, Box<dyn Error>> {
+        // Only the <article> contains the message, the rest is navigation
+        let text = page
+            .split_once("<article")
+            .and_then(|(_, a)| a.split_once("</article>"))
+            .map_or(page, |(a, _)| a);
+
+        let verdict = if text.contains("That's the right answer") {
+            Verdict::Correct
+        } else if text.contains("You gave an answer too recently") {
+            Verdict::RateLimited
+        } else if text.contains("You don't seem to be solving the right level") {
+            Verdict::WrongLevel
+        } else if text.contains("That's not the right answer") {
+            if text.contains("your answer is too high") {
+                Verdict::TooHigh
+            } else if text.contains("your answer is too low") {
+                Verdict::TooLow
+            } else {
+                Verdict::Incorrect
+            }
+        } else {
+            return Err(format!("Unrecognized response: {}", text.trim()).into());
+        };
+
+        Ok(Self {
+            verdict,
+            wait: parse_wait(text),
+        })
+    }
+}
+
+/// Extracts the wait time from messages like "You have 1m 5s left to wait" or "please wait 5 minutes"
+fn parse_wait(text: &str) -> Option<Duration> {
+    let left = Regex::new(r"You have (?:(\d+)m ?)?(?:(\d+)s)? left to wait").unwrap();
+    if let Some(c) = left.captures(text) {
+        let num = |i| c.get(i).map_or(0, |m| m.as_str().parse::<u64>().unwrap());
+        return Some(Duration::from_secs(num(1) * 60 + num(2)));
+    }
+
+    let minutes = Regex::new(r"wait (one|\d+) minutes?").unwrap();
+    minutes.captures(text).map(|c| {
+        let m = match &c[1] {
+            "one" => 1,
+            n => n.parse::<u64>().unwrap(),
+        };
+        Duration::from_secs(m * 60)
+    })
+}
+
+/// Where the input for a given day is stored, e.g. `input/2023/day5.txt`
+pub fn input_path(cache_dir: impl AsRef<Path>, year: u32, day: u32) -> PathBuf {
+    cache_dir
+        .as_ref()
+        .join(year.to_string())
+        .join(format!("day{day}.txt"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::TestServer;
+
+    #[test]
+    fn download_input_caches() {
+        let server = TestServer::start(|_| (200, "1 2 3\n4 5 6\n".to_owned()));
+        let dir = crate::test_server::temp_dir("download_input_caches");
+        let client = Client::new("s3cret").with_base_url(server.url());
+
+        let path = client.download_input(&dir, 2023, 9).unwrap();
+        assert_eq!(path, dir.join("2023").join("day9.txt"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1 2 3\n4 5 6\n");
+
+        // Second call is served from the cache
+        client.download_input(&dir, 2023, 9).unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].path, "/2023/day/9/input");
+        assert_eq!(requests[0].header("cookie"), Some("session=s3cret"));
+        assert_eq!(requests[0].header("user-agent"), Some(USER_AGENT));
+    }
+
+    #[test]
+    fn download_input_error_is_not_cached() {
+        let server = TestServer::start(|_| (400, "Please log in".to_owned()));
+        let dir = crate::test_server::temp_dir("download_input_error");
+        let client = Client::new("expired").with_base_url(server.url());
+
+        let err = client.download_input(&dir, 2023, 1).unwrap_err();
+        assert!(err.to_string().contains("400"));
+        assert!(!input_path(&dir, 2023, 1).exists());
+    }
+
+    fn page(msg: &str) -> String {
+        format!("<html><main><article><p>{msg}</p></article></main></html>")
+    }
+
+    #[test]
+    fn parse_submission() {
+        let cases = [
+            (
+                "That's the right answer! You are one gold star closer.",
+                Verdict::Correct,
+                None,
+            ),
+            (
+                "That's not the right answer; your answer is too high. Please wait one minute before trying again.",
+                Verdict::TooHigh,
+                Some(60),
+            ),
+            (
+                "That's not the right answer; your answer is too low. Please wait one minute before trying again.",
+                Verdict::TooLow,
+                Some(60),
+            ),
+            (
+                "That's not the right answer. Because you have guessed incorrectly 4 times on this puzzle, please wait 5 minutes before trying again.",
+                Verdict::Incorrect,
+                Some(300),
+            ),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again. You have 1m 5s left to wait.",
+                Verdict::RateLimited,
+                Some(65),
+            ),
+            (
+                "You gave an answer too recently. You have 42s left to wait.",
+                Verdict::RateLimited,
+                Some(42),
+            ),
+            (
+                "You don't seem to be solving the right level. Did you already complete it?",
+                Verdict::WrongLevel,
+                None,
+            ),
+        ];
+
+        for (msg, verdict, wait) in cases {
+            assert_eq!(
+                Submission::parse(&page(msg)).unwrap(),
+                Submission {
+                    verdict,
+                    wait: wait.map(Duration::from_secs)
+                },
+                "{msg}"
+            );
+        }
+
+        assert!(Submission::parse(&page("Something else")).is_err());
+    }
+
+    #[test]
+    fn submit_answer_posts_form() {
+        let server = TestServer::start(|_| (200, page("That's the right answer!")));
+        let client = Client::new("s3cret").with_base_url(server.url());
+
+        let res = client.submit_answer(2023, 7, 2, "5905").unwrap();
+        assert_eq!(res.verdict, Verdict::Correct);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/2023/day/7/answer");
+        assert_eq!(requests[0].body, "level=2&answer=5905");
+        assert_eq!(requests[0].header("cookie"), Some("session=s3cret"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Properties of the real inputs that some solvers rely on without the puzzle stating them.
+//!
+//! Days declare their assumptions in an `ASSUMPTIONS` list next to the solvers. If one of them
+//! does not hold for an input, the answer of that part can't be trusted.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::{day14, day17, day8, sandbox::panic_message};
+
+pub struct Assumption {
+    /// The part relying on it
+    pub part: u32,
+    pub description: &'static str,
+    /// Explains why the input breaks the assumption
+    pub check: fn(&str) -> Result<(), String>,
+}
+
+/// Everything the solvers of `day` assume about their input
+pub fn for_day(day: u32) -> &'static [Assumption] {
+    match day {
+        8 => day8::ASSUMPTIONS,
+        14 => day14::ASSUMPTIONS,
+        17 => day17::ASSUMPTIONS,
+        _ => &[],
+    }
+}
+
+/// Checks every assumption of the day against the input. A panicking check counts as failed.
+pub fn check(day: u32, input: &str) -> Vec<(&'static Assumption, Result<(), String>)> {
+    check_all(for_day(day), input)
+}
+
+fn check_all(
+    assumptions: &'static [Assumption],
+    input: &str,
+) -> Vec<(&'static Assumption, Result<(), String>)> {
+    assumptions
+        .iter()
+        .map(|a| {
+            let res = panic::catch_unwind(AssertUnwindSafe(|| (a.check)(input))).unwrap_or_else(
+                |payload| Err(format!("Check panicked: {}", panic_message(&*payload))),
+            );
+            (a, res)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failures(day: u32, input: &str) -> Vec<String> {
+        check(day, input)
+            .into_iter()
+            .filter_map(|(_, r)| r.err())
+            .collect()
+    }
+
+    #[test]
+    fn ghosts_cycle_cleanly() {
+        let input = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+        assert!(failures(8, input).is_empty());
+
+        // 11Z now leads to a second end node on a different schedule
+        let input = input.replace("11Z = (11B, XXX)", "11Z = (22Z, XXX)");
+        assert_eq!(
+            failures(8, &input),
+            ["11A reaches an end node after 3 steps, which is not a multiple of 2"]
+        );
+
+        let input = "L\n\n11A = (11B, 11B)\n11B = (11B, 11B)";
+        assert_eq!(failures(8, input), ["11A never reaches an end node"]);
+    }
+
+    #[test]
+    fn cycle_shortcut() {
+        let input = "O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+        assert!(failures(14, input).is_empty());
+
+        // Nothing moves, so the shortcut lands on the same state as every cycle
+        assert!(failures(14, "#O#\n###").is_empty());
+    }
+
+    #[test]
+    fn square_grid() {
+        assert!(failures(17, "123\n456\n789").is_empty());
+        assert_eq!(failures(17, "1234\n5678"), ["The grid is 4x2"]);
+    }
+
+    #[test]
+    fn panicking_check_fails() {
+        static PANICS: &[Assumption] = &[Assumption {
+            part: 1,
+            description: "Never checked properly",
+            check: |_| panic!("boom"),
+        }];
+        let res = check_all(PANICS, "").pop().unwrap().1;
+        assert_eq!(res, Err("Check panicked: boom".to_owned()));
+
+        assert_eq!(failures(17, "12\nab"), ["Invalid heat loss: a"]);
+        assert!(check(1, "").is_empty());
+    }
+}
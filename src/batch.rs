@@ -0,0 +1,230 @@
+//! Solves a whole directory of inputs (e.g. one per team member) with every variant.
+
+use std::{
+    error::Error,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use regex::Regex;
+
+use crate::{
+    registry::{self, Solution},
+    sandbox::{Outcome, Sandbox},
+};
+
+#[derive(Debug)]
+pub struct Row {
+    pub day: u32,
+    pub path: PathBuf,
+    pub results: Vec<(&'static Solution, Outcome)>,
+}
+
+impl Row {
+    /// Parts whose variants produced different answers
+    pub fn disagreements(&self) -> Vec<u32> {
+        let mut parts = vec![];
+        for part in [1, 2] {
+            let mut answers = self
+                .results
+                .iter()
+                .filter(|(s, _)| s.part == part)
+                .filter_map(|(_, o)| o.answer())
+                .collect::<Vec<_>>();
+            answers.dedup();
+            if answers.len() > 1 {
+                parts.push(part);
+            }
+        }
+        parts
+    }
+
+    pub fn panicked(&self) -> impl Iterator<Item = &'static Solution> + '_ {
+        self.results
+            .iter()
+            .filter(|(_, o)| matches!(o, Outcome::Panicked(_)))
+            .map(|(s, _)| *s)
+    }
+
+    pub fn timed_out(&self) -> impl Iterator<Item = &'static Solution> + '_ {
+        self.results
+            .iter()
+            .filter(|(_, o)| matches!(o, Outcome::TimedOut(_)))
+            .map(|(s, _)| *s)
+    }
+}
+
+/// Finds the input files in `dir` (recursively) and the day each one is for.
+///
+/// The day is taken from a `dayN` in the file name, or failing that in the parent directory name,
+/// so both `inputs/alice_day5.txt` and `inputs/day5/alice.txt` work. With `day` set, other days are
+/// skipped and files without a day in their path are assumed to be for it.
+pub fn collect_inputs(
+    dir: impl AsRef<Path>,
+    day: Option<u32>,
+) -> Result<Vec<(u32, PathBuf)>, Box<dyn Error>> {
+    let re = Regex::new(r"day(\d+)").unwrap();
+    let day_of = |p: &Path| {
+        let stem = p.file_stem()?.to_str()?;
+        let parent = p.parent()?.file_name()?.to_str()?;
+        re.captures(stem)
+            .or_else(|| re.captures(parent))
+            .and_then(|c| c[1].parse::<u32>().ok())
+    };
+
+    let mut inputs = vec![];
+    let mut dirs = vec![dir.as_ref().to_owned()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+
+            match (day_of(&path), day) {
+                (Some(d), Some(want)) if d != want => (),
+                (Some(d), _) | (None, Some(d)) => inputs.push((d, path)),
+                (None, None) => (),
+            }
+        }
+    }
+
+    inputs.sort();
+    Ok(inputs)
+}
+
+/// Runs every variant of the day on a single input, each in the sandbox
+pub fn solve_all(day: u32, path: PathBuf, sandbox: &Sandbox) -> Result<Row, Box<dyn Error>> {
+    let input: Arc<str> = fs::read_to_string(&path)?.into();
+    let results = registry::solutions()
+        .filter(|s| s.day == day)
+        .map(|s| (s, sandbox.run(s, input.clone())))
+        .collect();
+
+    Ok(Row { day, path, results })
+}
+
+/// One table per day: a row per input, a column per variant, followed by the flagged inputs
+pub fn render(rows: &[Row]) -> String {
+    let mut out = String::new();
+    let mut days = rows.iter().map(|r| r.day).collect::<Vec<_>>();
+    days.dedup();
+
+    for day in days {
+        let rows = rows.iter().filter(|r| r.day == day).collect::<Vec<_>>();
+        let mut table = vec![];
+
+        let mut header = vec!["Input".to_owned()];
+        header.extend(rows[0].results.iter().map(|(s, _)| match s.variant {
+            Some(v) => format!("Part {} ({v})", s.part),
+            None => format!("Part {}", s.part),
+        }));
+        table.push(header);
+
+        for r in &rows {
+            let mut line = vec![r.path.display().to_string()];
+            line.extend(r.results.iter().map(|(_, o)| o.to_string()));
+            table.push(line);
+        }
+
+        let widths = (0..table[0].len())
+            .map(|i| table.iter().map(|l| l[i].chars().count()).max().unwrap())
+            .collect::<Vec<_>>();
+        writeln!(out, "Day {day}").unwrap();
+        for line in table {
+            let cells = line
+                .iter()
+                .zip(&widths)
+                .map(|(c, w)| format!("{c:w$}"))
+                .collect::<Vec<_>>();
+            writeln!(out, "{}", cells.join(" | ").trim_end()).unwrap();
+        }
+
+        for r in &rows {
+            for part in r.disagreements() {
+                writeln!(
+                    out,
+                    "!! {}: variants disagree on part {part}",
+                    r.path.display()
+                )
+                .unwrap();
+            }
+            for s in r.panicked() {
+                writeln!(out, "!! {}: {s} panicked", r.path.display()).unwrap();
+            }
+            for s in r.timed_out() {
+                writeln!(out, "!! {}: {s} timed out", r.path.display()).unwrap();
+            }
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::temp_dir;
+
+    #[test]
+    fn collect_inputs_by_name() {
+        let dir = temp_dir("collect_inputs_by_name");
+        fs::create_dir_all(dir.join("day6")).unwrap();
+        fs::create_dir_all(dir.join("bob")).unwrap();
+        for f in [
+            "day6/alice.txt",
+            "bob/day9.txt",
+            "carol_day6.txt",
+            "notes.txt",
+        ] {
+            fs::write(dir.join(f), "").unwrap();
+        }
+
+        let days = |d| {
+            collect_inputs(&dir, d)
+                .unwrap()
+                .into_iter()
+                .map(|(d, p)| (d, p.strip_prefix(&dir).unwrap().to_owned()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            days(None),
+            [
+                (6, "carol_day6.txt".into()),
+                (6, "day6/alice.txt".into()),
+                (9, "bob/day9.txt".into())
+            ]
+        );
+        assert_eq!(
+            days(Some(9)),
+            [(9, "bob/day9.txt".into()), (9, "notes.txt".into())]
+        );
+    }
+
+    #[test]
+    fn flags_disagreements_and_errors() {
+        let dir = temp_dir("flags_disagreements_and_errors");
+        fs::write(dir.join("day0.txt"), ".#.").unwrap();
+        fs::write(dir.join("day9.txt"), "1 2 x").unwrap();
+
+        let rows = collect_inputs(&dir, None)
+            .unwrap()
+            .into_iter()
+            .map(|(d, p)| solve_all(d, p, &Sandbox::default()).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(rows[0].disagreements(), [1]);
+        assert_eq!(rows[0].panicked().count(), 0);
+        assert!(rows[1].disagreements().is_empty());
+        assert_eq!(rows[1].panicked().count(), 0);
+
+        let out = render(&rows);
+        assert!(out.contains("Part 1 (off_by_one)"));
+        assert!(out.contains("day0.txt: variants disagree on part 1"));
+        assert!(out.contains("ERROR: Could not parse x"));
+    }
+}
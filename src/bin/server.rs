@@ -0,0 +1,41 @@
+use std::{error::Error, time::Duration};
+
+use advent_of_code_2023::server::{Limits, Server};
+use clap::Parser;
+
+#[derive(Parser)]
+#[command(about = "Solve Advent of Code 2023 inputs over HTTP")]
+struct Args {
+    #[arg(long, default_value = "127.0.0.1:8023")]
+    addr: String,
+    /// Largest accepted input in bytes
+    #[arg(long, default_value_t = Limits::default().max_body)]
+    max_body: usize,
+    /// Per-request solve timeout in seconds
+    #[arg(long, default_value_t = Limits::default().timeout.as_secs_f64())]
+    timeout: f64,
+    /// Solves running at once, timed out ones included
+    #[arg(long, default_value_t = Limits::default().max_solves)]
+    max_solves: usize,
+    /// Connections handled at once
+    #[arg(long, default_value_t = Limits::default().max_connections)]
+    max_connections: usize,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse();
+    let server = Server::bind(
+        &args.addr,
+        Limits {
+            max_body: args.max_body,
+            timeout: Duration::from_secs_f64(args.timeout),
+            max_solves: args.max_solves,
+            max_connections: args.max_connections,
+        },
+    )?;
+
+    eprintln!("Listening on http://{}", server.local_addr()?);
+    server.serve();
+
+    Ok(())
+}
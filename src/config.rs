@@ -0,0 +1,157 @@
+use std::{collections::BTreeMap, env, error::Error, fs, io::ErrorKind, path::Path};
+
+use serde::Deserialize;
+
+use crate::registry::{self, Solution};
+
+/// Default location of the config file, relative to the working directory
+pub const CONFIG_FILE: &str = "aoc.toml";
+
+/// Environment variable that overrides the session token from the config file
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Value of the `session` cookie from adventofcode.com
+    pub session: Option<String>,
+    /// Variant to run when none is asked for, e.g. `day12 = { part1 = "regex" }`
+    pub variants: BTreeMap<String, BTreeMap<String, String>>,
+    pub scaling: ScalingConfig,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScalingConfig {
+    /// Growth exponent above which the `scaling` command flags a solver
+    pub max_exponent: f64,
+}
+
+impl Default for ScalingConfig {
+    fn default() -> Self {
+        Self { max_exponent: 1.5 }
+    }
+}
+
+impl Config {
+    /// Loads the config from `path`. A missing file is not an error and yields the defaults.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let path = path.as_ref();
+        match fs::read_to_string(path) {
+            Ok(s) => {
+                let config: Self =
+                    toml::from_str(&s).map_err(|e| format!("{}: {e}", path.display()))?;
+                config
+                    .check_variants()
+                    .map_err(|e| format!("{}: {e}", path.display()))?;
+                Ok(config)
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("{}: {e}", path.display()).into()),
+        }
+    }
+
+    /// The session token, taken from `AOC_SESSION` if set, otherwise from the config file.
+    pub fn session(&self) -> Result<String, Box<dyn Error>> {
+        env::var(SESSION_ENV)
+            .ok()
+            .or_else(|| self.session.clone())
+            .map(|s| s.trim().to_owned())
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| {
+                format!("No session token: set {SESSION_ENV} or `session` in {CONFIG_FILE}").into()
+            })
+    }
+
+    /// The configured variant of a part, `None` for the unnamed one
+    pub fn variant(&self, day: u32, part: u32) -> Option<&str> {
+        self.variants
+            .get(&format!("day{day}"))?
+            .get(&format!("part{part}"))
+            .map(String::as_str)
+    }
+
+    /// The solution to run: `variant` if given, otherwise the configured one, otherwise the
+    /// unnamed one
+    pub fn solution(
+        &self,
+        day: u32,
+        part: u32,
+        variant: Option<&str>,
+    ) -> Result<&'static Solution, Box<dyn Error>> {
+        registry::select(day, part, variant.or_else(|| self.variant(day, part)))
+    }
+
+    fn check_variants(&self) -> Result<(), Box<dyn Error>> {
+        let number = |key: &str, prefix| {
+            key.strip_prefix(prefix)
+                .and_then(|n| n.parse().ok())
+                .ok_or_else(|| format!("Invalid key in [variants]: {key}"))
+        };
+        for (day, parts) in &self.variants {
+            let day = number(day, "day")?;
+            for (part, name) in parts {
+                registry::select(day, number(part, "part")?, Some(name))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_session() {
+        let config: Config = toml::from_str(r#"session = "abc123""#).unwrap();
+        assert_eq!(config.session.as_deref(), Some("abc123"));
+        assert_eq!(config.scaling.max_exponent, 1.5);
+    }
+
+    #[test]
+    fn missing_file_is_default() {
+        let config = Config::load("does/not/exist.toml").unwrap();
+        assert!(config.session.is_none());
+    }
+
+    #[test]
+    fn variant_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+            [variants]
+            day6 = { part2 = "quadratic" }
+            day12 = { part1 = "default" }
+            "#,
+        )
+        .unwrap();
+        config.check_variants().unwrap();
+        assert_eq!(config.variant(6, 2), Some("quadratic"));
+        assert_eq!(config.variant(6, 1), None);
+        assert_eq!(
+            config.solution(6, 2, None).unwrap().variant,
+            Some("quadratic")
+        );
+        assert_eq!(
+            config.solution(6, 2, Some("default")).unwrap().variant,
+            None
+        );
+        assert_eq!(config.solution(12, 1, None).unwrap().variant, None);
+
+        let bad = |toml| {
+            toml::from_str::<Config>(toml)
+                .unwrap()
+                .check_variants()
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            bad("variants.day6.part2 = \"fast\""),
+            "Day 6 part 2 has no variant \"fast\", available: default, quadratic"
+        );
+        assert_eq!(
+            bad("variants.six.part2 = \"fast\""),
+            "Invalid key in [variants]: six"
+        );
+    }
+}
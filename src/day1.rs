@@ -1,81 +1,74 @@
-use aoc_runner_derive::{aoc, aoc_generator};
+use std::error::Error;
 
-#[aoc_generator(day1)]
-fn parse(file: &str) -> Vec<String> {
-    file.lines().map(String::from).collect::<Vec<_>>()
+use aoc_runner_derive::aoc;
+
+use crate::input::{self, Record};
+
+fn calibration(line: &str) -> Result<i64, Box<dyn Error>> {
+    let digits = line
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<Vec<_>>();
+
+    Ok((digits
+        .first()
+        .ok_or_else(|| format!("A line has no digits (must be >= 1): {line}"))?
+        .to_string()
+        + &digits
+            .last()
+            .expect("A char just disappeared from an array")
+            .to_string())
+        .parse::<i64>()?)
+}
+
+fn spell_out(line: &str) -> String {
+    let mut l = line.replace("one", "one1one");
+    l = l.replace("two", "two2two");
+    l = l.replace("three", "three3three");
+    l = l.replace("four", "four4four");
+    l = l.replace("five", "five5five");
+    l = l.replace("six", "six6six");
+    l = l.replace("seven", "seven7seven");
+    l = l.replace("eight", "eight8eight");
+    l = l.replace("nine", "nine9nine");
+    l
 }
 
 #[aoc(day1, part1)]
-fn part1(lines: &[String]) -> i64 {
-    let mut result = 0;
-    for l in lines {
-        let digits = l.chars().filter(char::is_ascii_digit).collect::<Vec<_>>();
+fn part1(input: &str) -> Result<i64, Box<dyn Error>> {
+    part1_lines(input::str_lines(input))
+}
 
-        result += (digits
-            .first()
-            .expect("A line has no digits (must be >= 1).")
-            .to_string()
-            + &digits
-                .last()
-                .expect("A char just disappeared from an array")
-                .to_string())
-            .parse::<i64>()
-            .unwrap();
-    }
+#[aoc(day1, part2)]
+fn part2(input: &str) -> Result<i64, Box<dyn Error>> {
+    part2_lines(input::str_lines(input))
+}
 
-    result
+pub(crate) fn part1_lines<'a>(
+    lines: impl Iterator<Item = Record<'a>>,
+) -> Result<i64, Box<dyn Error>> {
+    lines.map(|l| calibration(&l?)).sum()
 }
 
-#[aoc(day1, part2)]
-fn part2(lines: &[String]) -> i64 {
-    let lines = lines
-        .iter()
-        .map(|l| {
-            let mut l = l.replace("one", "one1one");
-            l = l.replace("two", "two2two");
-            l = l.replace("three", "three3three");
-            l = l.replace("four", "four4four");
-            l = l.replace("five", "five5five");
-            l = l.replace("six", "six6six");
-            l = l.replace("seven", "seven7seven");
-            l = l.replace("eight", "eight8eight");
-            l = l.replace("nine", "nine9nine");
-            l
-        })
-        .collect::<Vec<_>>();
-    part1(&lines)
+pub(crate) fn part2_lines<'a>(
+    lines: impl Iterator<Item = Record<'a>>,
+) -> Result<i64, Box<dyn Error>> {
+    lines.map(|l| calibration(&spell_out(&l?))).sum()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
-    #[test]
-    fn part1_example() {
-        assert_eq!(
-            part1(&parse(
-                "1abc2
+    examples! {
+        "1abc2
         pqr3stu8vwx
         a1b2c3d4e5f
-        treb7uchet"
-            )),
-            142
-        );
-    }
-
-    #[test]
-    fn part2_example() {
-        assert_eq!(
-            part2(&parse(
-                "two1nine
+        treb7uchet" => part1: 142;
+        "two1nine
         eightwothree
         abcone2threexyz
         xtwone3four
         4nineeightseven2
         zoneight234
-        7pqrstsixteen"
-            )),
-            281
-        );
+        7pqrstsixteen" => part2: 281;
     }
 }
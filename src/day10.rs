@@ -1,7 +1,14 @@
-use std::collections::HashSet;
+use std::error::Error;
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::{
+    dot::Graph,
+    geometry, graph,
+    grid::{Grid, Pos, DIRS},
+    hash::FxHashSet,
+};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum Pipe {
     NS,
@@ -14,9 +21,11 @@ enum Pipe {
     Start,
 }
 
-impl From<char> for Pipe {
-    fn from(c: char) -> Self {
-        match c {
+impl TryFrom<char> for Pipe {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, String> {
+        Ok(match c {
             '|' => Self::NS,
             '-' => Self::EW,
             'L' => Self::NE,
@@ -25,237 +34,172 @@ impl From<char> for Pipe {
             'F' => Self::SE,
             '.' => Self::Ground,
             'S' => Self::Start,
-            _ => panic!("Invalid character: {c}"),
-        }
+            _ => return Err(format!("Invalid character: {c}")),
+        })
     }
 }
 
-// type ParsedInput = ((usize, usize), Vec<Vec<Pipe>>);
-type ParsedInput = ((usize, usize), Vec<Vec<Pipe>>);
-
-#[aoc_generator(day10)]
-fn parse(input: &str) -> ParsedInput {
-    let mut start = (0, 0);
-    let map = input
-        .lines()
-        .enumerate()
-        .map(|(y, l)| {
-            l.chars()
-                .enumerate()
-                .map(|(x, c)| {
-                    if c == 'S' {
-                        start = (x, y);
-                    };
-                    Pipe::from(c)
-                })
-                .collect::<Vec<_>>()
-        })
-        .collect::<Vec<_>>();
+type ParsedInput = (Pos, Grid<Pipe>);
+
+impl Pipe {
+    fn symbol(&self) -> char {
+        match self {
+            Self::NS => '|',
+            Self::EW => '-',
+            Self::NE => 'L',
+            Self::NW => 'J',
+            Self::SW => '7',
+            Self::SE => 'F',
+            Self::Ground => '.',
+            Self::Start => 'S',
+        }
+    }
 
-    (start, map)
+    /// Directions the pipe connects to
+    fn connections(&self) -> &'static [(isize, isize)] {
+        match self {
+            Self::NS => &[(0, -1), (0, 1)],
+            Self::EW => &[(1, 0), (-1, 0)],
+            Self::NE => &[(0, -1), (1, 0)],
+            Self::NW => &[(0, -1), (-1, 0)],
+            Self::SW => &[(0, 1), (-1, 0)],
+            Self::SE => &[(0, 1), (1, 0)],
+            Self::Ground => &[],
+            // Whatever fits, the neighbours have to connect back anyway
+            Self::Start => &DIRS,
+        }
+    }
 }
 
-fn find_loop(start: &(usize, usize), map: &Vec<Vec<Pipe>>) -> HashSet<(usize, usize)> {
-    let mut visited = HashSet::new();
-    let mut queue = vec![(start.0 as i32, start.1 as i32)];
-
-    while let Some((x, y)) = queue.pop() {
-        if y >= 0
-            && (y as usize) < map.len()
-            && x >= 0
-            && (x as usize) < map[y as usize].len()
-            && !visited.contains(&(x as usize, y as usize))
-        {
-            visited.insert((x as usize, y as usize));
-            match map[y as usize][x as usize] {
-                Pipe::NS => {
-                    queue.push((x, y - 1));
-                    queue.push((x, y + 1));
-                }
-                Pipe::EW => {
-                    queue.push((x + 1, y));
-                    queue.push((x - 1, y));
-                }
-                Pipe::NE => {
-                    queue.push((x, y - 1));
-                    queue.push((x + 1, y));
-                }
-                Pipe::NW => {
-                    queue.push((x, y - 1));
-                    queue.push((x - 1, y));
-                }
-                Pipe::SW => {
-                    queue.push((x, y + 1));
-                    queue.push((x - 1, y));
-                }
-                Pipe::SE => {
-                    queue.push((x, y + 1));
-                    queue.push((x + 1, y));
-                }
-                Pipe::Ground => (),
-                Pipe::Start => {
-                    if y > 0 {
-                        if let Some(Pipe::NS | Pipe::SW | Pipe::SE) =
-                            map.get(y as usize - 1).and_then(|row| row.get(x as usize))
-                        {
-                            queue.push((x, y - 1));
-                        }
-                    }
+#[aoc_generator(day10)]
+fn parse(input: &str) -> Result<ParsedInput, Box<dyn Error>> {
+    let map = Grid::try_parse(input, Pipe::try_from)?;
+    let start = map
+        .iter()
+        .find(|(_, p)| **p == Pipe::Start)
+        .ok_or("No start tile")?
+        .0;
+
+    Ok((start, map))
+}
 
-                    if let Some(Pipe::NE | Pipe::NS | Pipe::NW) =
-                        map.get(y as usize + 1).and_then(|row| row.get(x as usize))
-                    {
-                        queue.push((x, y + 1));
-                    }
-                    if x > 0 {
-                        if let Some(Pipe::SE | Pipe::NE | Pipe::EW) =
-                            map.get(y as usize).and_then(|row| row.get(x as usize - 1))
-                        {
-                            queue.push((x - 1, y));
-                        }
-                    }
-                    if let Some(Pipe::NW | Pipe::SW | Pipe::EW) =
-                        map.get(y as usize).and_then(|row| row.get(x as usize + 1))
-                    {
-                        queue.push((x + 1, y));
-                    }
-                }
-            }
-        }
-    }
+/// Neighbours that `pos` connects to and that connect back to it
+fn connected(map: &Grid<Pipe>, pos: Pos) -> Vec<Pos> {
+    map[pos]
+        .connections()
+        .iter()
+        .filter_map(|&(dx, dy)| {
+            let next = map.step(pos, (dx, dy))?;
+            map[next]
+                .connections()
+                .contains(&(-dx, -dy))
+                .then_some(next)
+        })
+        .collect()
+}
 
-    visited
+fn find_loop(start: Pos, map: &Grid<Pipe>) -> FxHashSet<Pos> {
+    graph::reachable([start], |&p| connected(map, p))
 }
 
 #[aoc(day10, part1)]
 fn part1((start, map): &ParsedInput) -> usize {
-    find_loop(start, map).len() / 2
+    find_loop(*start, map).len() / 2
 }
 
-const DIRS: [(i32, i32); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
-
 #[aoc(day10, part2)]
 fn part2((start, map): &ParsedInput) -> usize {
-    let loop_cells = find_loop(start, map);
-    let mut map = map.clone();
-    let mut empty = HashSet::new();
-    let mut queue = vec![];
-
-    let ymax = map.len() - 1;
-    for (y, line) in map.iter_mut().enumerate() {
-        let xmax = line.len() - 1;
-        for (x, c) in line.iter_mut().enumerate() {
-            if !loop_cells.contains(&(x, y)) {
-                if x == 0 || x == xmax || y == 0 || y == ymax {
-                    queue.push((x as i32 * 2, y as i32 * 2));
-                }
-                *c = Pipe::Ground;
-                empty.insert((x as i32, y as i32));
-            }
+    let loop_cells = find_loop(*start, map);
+
+    // Upscale the map so squeezing between pipes becomes moving through a gap cell: every tile
+    // becomes 2x2, with the pipe in the top left and its connections east and south filled in
+    let mut walls = Grid::new(map.width() * 2, map.height() * 2, false);
+    for &(x, y) in &loop_cells {
+        let pipe = &map[(x, y)];
+        walls[(x * 2, y * 2)] = true;
+        if matches!(pipe, Pipe::EW | Pipe::NE | Pipe::SE | Pipe::Start) {
+            walls[(x * 2 + 1, y * 2)] = true;
+        }
+        if matches!(pipe, Pipe::NS | Pipe::SW | Pipe::SE | Pipe::Start) {
+            walls[(x * 2, y * 2 + 1)] = true;
+        }
+        if *pipe == Pipe::Start {
+            walls[(x * 2 + 1, y * 2 + 1)] = true;
         }
     }
 
-    let mut upscaled: Vec<Vec<Pipe>> = Vec::with_capacity(map.len() * 2);
-    for line in map {
-        let mut cur = Vec::with_capacity(line.len() * 2);
-        let mut next = Vec::with_capacity(line.len() * 2);
-        for c in line {
-            match c {
-                Pipe::EW => {
-                    cur.extend_from_slice(&[Pipe::EW, Pipe::EW]);
-                    next.extend_from_slice(&[Pipe::Ground, Pipe::Ground]);
-                }
-                Pipe::NS => {
-                    cur.extend_from_slice(&[Pipe::NS, Pipe::Ground]);
-                    next.extend_from_slice(&[Pipe::NS, Pipe::Ground]);
-                }
-                Pipe::NE => {
-                    cur.extend_from_slice(&[Pipe::NE, Pipe::EW]);
-                    next.extend_from_slice(&[Pipe::Ground, Pipe::Ground]);
-                }
-                Pipe::NW => {
-                    cur.extend_from_slice(&[Pipe::NW, Pipe::Ground]);
-                    next.extend_from_slice(&[Pipe::Ground, Pipe::Ground]);
-                }
-                Pipe::SW => {
-                    cur.extend_from_slice(&[Pipe::SW, Pipe::Ground]);
-                    next.extend_from_slice(&[Pipe::NS, Pipe::Ground]);
-                }
-                Pipe::SE => {
-                    cur.extend_from_slice(&[Pipe::SE, Pipe::EW]);
-                    next.extend_from_slice(&[Pipe::NS, Pipe::Ground]);
-                }
-                Pipe::Ground => {
-                    cur.extend_from_slice(&[Pipe::Ground, Pipe::Ground]);
-                    next.extend_from_slice(&[Pipe::Ground, Pipe::Ground]);
-                }
-                Pipe::Start => {
-                    cur.extend_from_slice(&[Pipe::NS, Pipe::EW]);
-                    next.extend_from_slice(&[Pipe::EW, Pipe::NS]);
-                }
-            }
-        }
-        upscaled.push(cur);
-        upscaled.push(next);
+    let outside_edge = map
+        .positions()
+        .filter(|&p| map.is_edge(p) && !loop_cells.contains(&p))
+        .map(|(x, y)| (x * 2, y * 2));
+    let outside = graph::flood_fill(&walls, outside_edge, |_, &wall| !wall);
+
+    map.positions()
+        .filter(|&(x, y)| !loop_cells.contains(&(x, y)) && !outside[(x * 2, y * 2)])
+        .count()
+}
+
+/// The loop tiles in order, starting from `start`
+fn loop_path(start: Pos, map: &Grid<Pipe>) -> Result<Vec<Pos>, Box<dyn Error>> {
+    let mut path = vec![start];
+    let mut prev = start;
+    let mut cur = *connected(map, start).first().ok_or("There is no loop")?;
+    while cur != start {
+        path.push(cur);
+        let next = connected(map, cur)
+            .into_iter()
+            .find(|&n| n != prev)
+            .ok_or("The loop is broken")?;
+        (prev, cur) = (cur, next);
     }
+    Ok(path)
+}
 
-    let mut visited: HashSet<(i32, i32)> = HashSet::new();
-    while let Some((x, y)) = queue.pop() {
-        if visited.insert((x, y)) {
-            for (dx, dy) in DIRS {
-                let x = x + dx;
-                let y = y + dy;
+/// Every loop tile is a vertex of the polygon, so the enclosed tiles are its interior lattice points
+#[aoc(day10, part2, shoelace)]
+fn part2_shoelace((start, map): &ParsedInput) -> Result<i64, Box<dyn Error>> {
+    let vertices = loop_path(*start, map)?
+        .into_iter()
+        .map(|(x, y)| (x as i64, y as i64))
+        .collect::<Vec<_>>();
 
-                if y >= 0
-                    && (y as usize) < upscaled.len()
-                    && x >= 0
-                    && (x as usize) < upscaled[y as usize].len()
-                    && upscaled[y as usize][x as usize] == Pipe::Ground
-                {
-                    queue.push((x, y));
-                }
-            }
+    Ok(geometry::interior_points(&vertices))
+}
+
+/// The loop as a Graphviz graph, every tile pinned to its place for `neato`
+pub(crate) fn dot(input: &str) -> Result<String, Box<dyn Error>> {
+    let (start, map) = parse(input)?;
+    let path = loop_path(start, &map)?;
+    let id = |(x, y): Pos| format!("{x},{y}");
+
+    let mut g = Graph::undirected("day10");
+    g.attr("layout", "neato");
+    for &(x, y) in &path {
+        let label = format!("{}\n{x},{y}", map[(x, y)].symbol());
+        let pos = format!("{x},-{y}!");
+        let mut attrs = vec![("label", label.as_str()), ("pos", pos.as_str())];
+        if (x, y) == start {
+            attrs.extend([("style", "filled"), ("fillcolor", "gold")]);
         }
+        g.node(&id((x, y)), &attrs);
     }
-
-    empty
-        .difference(
-            &visited
-                .iter()
-                .filter_map(|(x, y)| {
-                    if x % 2 == 0
-                        && y % 2 == 0
-                        && upscaled[*y as usize][*x as usize] == Pipe::Ground
-                    {
-                        Some((x / 2, y / 2))
-                    } else {
-                        None
-                    }
-                })
-                .collect::<HashSet<_>>(),
-        )
-        .count()
+    for (i, &p) in path.iter().enumerate() {
+        g.edge(&id(p), &id(path[(i + 1) % path.len()]), &[]);
+    }
+    Ok(g.render())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     const TESTCASE: &str = r#".....
 .S-7.
 .|.|.
 .L-J.
 ....."#;
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 4);
-    }
 
-    #[test]
-    fn part2_example1() {
-        assert_eq!(
-            part2(&parse(
-                r#"...........
+    examples! {
+        TESTCASE => part1: 4;
+        r#"...........
 .S-------7.
 .|F-----7|.
 .||.....||.
@@ -263,17 +207,8 @@ mod tests {
 .|L-7.F-J|.
 .|..|.|..|.
 .L--J.L--J.
-..........."#
-            )),
-            4
-        );
-    }
-
-    #[test]
-    fn part2_example2() {
-        assert_eq!(
-            part2(&parse(
-                r#"..........
+..........."# => part2: 4;
+        r#"..........
 .S------7.
 .|F----7|.
 .||....||.
@@ -281,17 +216,8 @@ mod tests {
 .|L-7F-J|.
 .|..||..|.
 .L--JL--J.
-.........."#
-            )),
-            4
-        );
-    }
-
-    #[test]
-    fn part2_example3() {
-        assert_eq!(
-            part2(&parse(
-                r#".F----7F7F7F7F-7....
+.........."# => part2: 4;
+        r#".F----7F7F7F7F-7....
 .|F--7||||||||FJ....
 .||.FJ||||||||L7....
 FJL7L7LJLJ||LJ.L-7..
@@ -300,17 +226,8 @@ L--J.L7...LJS7F-7L7.
 ....L7.F7||L7|.L7L7|
 .....|FJLJ|FJ|F7|.LJ
 ....FJL-7.||.||||...
-....L---J.LJ.LJLJ..."#
-            )),
-            8
-        );
-    }
-
-    #[test]
-    fn part2_example4() {
-        assert_eq!(
-            part2(&parse(
-                r#"FF7FSF7F7F7F7F7F---7
+....L---J.LJ.LJLJ..."# => part2: 8;
+        r#"FF7FSF7F7F7F7F7F---7
 L|LJ||||||||||||F--J
 FL-7LJLJ||||||LJL-77
 F--JF--7||LJLJ7F7FJ-
@@ -319,9 +236,11 @@ L---JF-JLJ.||-FJLJJ7
 |FFJF7L7F-JF7|JL---7
 7-L-JL7||F7|L7F-7F7|
 L.L7LFJ|||||FJL7||LJ
-L7JLJL-JLJLJL--JLJ.L"#
-            )),
-            10
-        );
+L7JLJL-JLJLJL--JLJ.L"# => part2: 10;
+    }
+
+    #[test]
+    fn dot() {
+        crate::dot::assert_golden("day10.dot", &super::dot(TESTCASE).unwrap());
     }
 }
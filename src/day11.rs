@@ -91,14 +91,10 @@ mod tests {
 ..........
 .......#..
 #...#....."#;
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 374);
-    }
-    // The website didn't give a test result, but solving p2 was easy enough to calculate it myself
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse(TESTCASE)), 82000210);
+
+    // The website didn't give a result for part 2, but solving it was easy enough to calculate it myself
+    examples! {
+        TESTCASE => part1: 374, part2: 82000210;
     }
 
     #[test]
@@ -1,38 +1,49 @@
+use std::error::Error;
+
 use aoc_runner_derive::{aoc, aoc_generator};
-use cached::proc_macro::cached;
 use regex::bytes::Regex;
 use smallvec::SmallVec;
 
+use crate::{input::Record, memo::Memo, parallel, progress};
+
 type ParsedInput = Vec<(String, Vec<u16>)>;
 
+type Springs = SmallVec<[char; 64]>;
+type Groups = SmallVec<[u16; 8]>;
+
 #[aoc_generator(day12)]
-fn parse(input: &str) -> ParsedInput {
-    input
-        .lines()
-        .map(|l| {
-            let (springs, nums) = l.split_once(' ').unwrap();
-            (
-                springs.to_owned(),
-                nums.split(',')
-                    .map(|n| n.parse::<u16>().expect("Invalid number"))
-                    .collect::<Vec<_>>(),
-            )
+fn parse(input: &str) -> Result<ParsedInput, Box<dyn Error>> {
+    input.lines().map(parse_row).collect()
+}
+
+fn parse_row(line: &str) -> Result<(String, Vec<u16>), Box<dyn Error>> {
+    let (springs, nums) = line.split_once(' ').ok_or("Missing groups")?;
+    if let Some(c) = springs.chars().find(|c| !matches!(c, '.' | '#' | '?')) {
+        return Err(format!("Unknown char: {c}").into());
+    }
+    let groups = nums
+        .split(',')
+        .map(|n| match n.parse::<u16>() {
+            Ok(0) => Err("Empty group".into()),
+            Ok(n) => Ok(n),
+            Err(e) => Err(format!("Invalid number {n}: {e}").into()),
         })
-        .collect::<Vec<_>>()
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+    Ok((springs.to_owned(), groups))
 }
 
 #[aoc(day12, part1, regex)]
-fn part1(input: &ParsedInput) -> i64 {
+fn part1(input: &ParsedInput) -> Result<i64, Box<dyn Error>> {
     let mut variants = 0;
-    for (line, nums) in input {
+    for (i, (line, nums)) in input.iter().enumerate() {
+        progress::report("rows", i as u64, input.len() as u64);
         let mut cur_variants = 0;
-        // println!("{line} {nums:?}");
         let mut s = format!(".{line}.").bytes().collect::<Vec<_>>();
         let mut groups = vec![];
         for n in nums {
             groups.push(format!(r"([+#]{{{n}}})"));
         }
-        let re = Regex::new(&format!("^[^+#]*{}[^+#]*$", groups.join("[^+#]+"))).unwrap();
+        let re = Regex::new(&format!("^[^+#]*{}[^+#]*$", groups.join("[^+#]+")))?;
 
         let mut cur = 0;
         while cur != s.len() {
@@ -57,22 +68,16 @@ fn part1(input: &ParsedInput) -> i64 {
             cur_variants = 1;
         }
         variants += cur_variants;
-        // println!("Done {line}");
+        trace!(%line, ?nums, variants = cur_variants, "row done");
     }
 
-    variants
+    Ok(variants)
 }
 
 #[aoc(day12, part1)]
 fn part1_recursive(input: &ParsedInput) -> i64 {
-    input
+    parallel::map(input, |(springs, groups)| arrangements(springs, groups))
         .iter()
-        .map(|(springs, groups)| {
-            solve(
-                springs.clone().chars().collect::<SmallVec<_>>(),
-                groups.clone().into(),
-            )
-        })
         .sum()
 }
 
@@ -81,19 +86,60 @@ fn part2(input: &ParsedInput) -> i64 {
     part1_recursive(
         &input
             .iter()
-            .map(|(springs, nums)| {
-                let mut springs = springs.clone();
-                springs.push('?');
-                springs = springs.repeat(5);
-                springs.pop();
-                (springs, nums.repeat(5))
-            })
+            .map(|(springs, nums)| unfold(springs, nums))
             .collect::<Vec<_>>(),
     )
 }
 
-#[cached]
-fn solve(mut springs: SmallVec<[char; 64]>, mut groups: SmallVec<[u16; 8]>) -> i64 {
+pub(crate) fn part1_lines<'a>(
+    lines: impl Iterator<Item = Record<'a>>,
+) -> Result<i64, Box<dyn Error>> {
+    lines
+        .map(|l| {
+            let (springs, groups) = parse_row(&l?)?;
+            Ok(arrangements(&springs, &groups))
+        })
+        .sum()
+}
+
+pub(crate) fn part2_lines<'a>(
+    lines: impl Iterator<Item = Record<'a>>,
+) -> Result<i64, Box<dyn Error>> {
+    lines
+        .map(|l| {
+            let (springs, groups) = parse_row(&l?)?;
+            let (springs, groups) = unfold(&springs, &groups);
+            Ok(arrangements(&springs, &groups))
+        })
+        .sum()
+}
+
+fn arrangements(springs: &str, groups: &[u16]) -> i64 {
+    // Every row gets its own cache, so it is freed as soon as the row is solved
+    let mut memo = Memo::new();
+    solve(springs.chars().collect(), groups.into(), &mut memo)
+}
+
+/// The row as it is in part 2: five copies of the springs joined by `?`, five of the groups
+fn unfold(springs: &str, groups: &[u16]) -> (String, Vec<u16>) {
+    let mut springs = springs.to_owned();
+    springs.push('?');
+    springs = springs.repeat(5);
+    springs.pop();
+    (springs, groups.repeat(5))
+}
+
+fn solve(springs: Springs, groups: Groups, memo: &mut Memo<(Springs, Groups), i64>) -> i64 {
+    memo.get_or_insert_with((springs.clone(), groups.clone()), |memo| {
+        solve_uncached(springs, groups, memo)
+    })
+}
+
+fn solve_uncached(
+    mut springs: Springs,
+    mut groups: Groups,
+    memo: &mut Memo<(Springs, Groups), i64>,
+) -> i64 {
     // Groups are satisfied, no more broken springs
     if groups.is_empty() {
         if springs.iter().all(|c| *c == '.' || *c == '?') {
@@ -104,7 +150,7 @@ fn solve(mut springs: SmallVec<[char; 64]>, mut groups: SmallVec<[u16; 8]>) -> i
 
     match springs.pop() {
         Some('?') => {
-            let res = solve(springs.clone(), groups.clone());
+            let res = solve(springs.clone(), groups.clone(), memo);
             let g = groups.pop().unwrap() - 1;
             if g as usize > springs.len() {
                 return 0;
@@ -113,10 +159,10 @@ fn solve(mut springs: SmallVec<[char; 64]>, mut groups: SmallVec<[u16; 8]>) -> i
             let mut springs = SmallVec::from(springs);
             if cur.iter().all(|c| *c == '#' || *c == '?') {
                 if let Some('.' | '?') | None = springs.pop() {
-                    return res + solve(springs, groups.clone());
+                    return res + solve(springs, groups.clone(), memo);
                 }
             }
-            return res;
+            res
         }
         Some('#') => {
             let g = groups.pop().unwrap() - 1;
@@ -127,23 +173,21 @@ fn solve(mut springs: SmallVec<[char; 64]>, mut groups: SmallVec<[u16; 8]>) -> i
             let mut springs = SmallVec::from(springs);
             if cur.iter().all(|c| *c == '#' || *c == '?') {
                 if let Some('.' | '?') | None = springs.pop() {
-                    return solve(springs, groups.clone());
+                    return solve(springs, groups.clone(), memo);
                 }
             }
 
-            return 0;
+            0
         }
-        Some('.') => return solve(springs, groups),
+        Some('.') => solve(springs, groups, memo),
+        // Rejected by `parse`
         Some(c) => unreachable!("Unknown char: {c}"),
-        None => {
-            return 0;
-        }
+        None => 0,
     }
 }
 
 #[cfg(test)]
 mod tests {
-
     use super::*;
 
     const TESTCASE: &str = r"???.### 1,1,3
@@ -152,14 +196,13 @@ mod tests {
 ????.#...#... 4,1,1
 ????.######..#####. 1,6,5
 ?###???????? 3,2,1";
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 21);
+
+    examples! {
+        TESTCASE => part1: 21, part2: 525152;
     }
 
-    #[test]
-    fn part1_recursive_example() {
-        assert_eq!(part1_recursive(&parse(TESTCASE)), 21);
+    fn parse(input: &str) -> ParsedInput {
+        super::parse(input).unwrap()
     }
 
     #[test]
@@ -190,9 +233,4 @@ mod tests {
         assert_eq!(part1_recursive(&parse("?##?.??.??? 2,1,2")), 4);
         assert_eq!(part1_recursive(&parse("?#?#????????? 2,2,4,1")), 3);
     }
-
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse(TESTCASE)), 525152);
-    }
 }
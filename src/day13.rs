@@ -1,14 +1,31 @@
+use std::error::Error;
+
 use aoc_runner_derive::{aoc, aoc_generator};
 use smallvec::SmallVec;
 
+use crate::parallel;
+
 type ParsedInput = Vec<SmallVec<[SmallVec<[char; 32]>; 32]>>;
 
 #[aoc_generator(day13)]
-fn parse(input: &str) -> ParsedInput {
+fn parse(input: &str) -> Result<ParsedInput, Box<dyn Error>> {
     input
         .split("\n\n")
-        .map(|pat| pat.lines().map(|l| l.chars().collect::<_>()).collect::<_>())
-        .collect::<_>()
+        .map(|pat| {
+            let pat = pat
+                .lines()
+                .map(|l| l.chars().collect::<SmallVec<_>>())
+                .collect::<SmallVec<[SmallVec<_>; 32]>>();
+            let width = pat.first().map_or(0, |l| l.len());
+            if width == 0 || pat.iter().any(|l| l.len() != width) {
+                return Err("A pattern is empty or not rectangular".into());
+            }
+            if let Some(c) = pat.iter().flatten().find(|c| !matches!(c, '.' | '#')) {
+                return Err(format!("Invalid char: {c}").into());
+            }
+            Ok(pat)
+        })
+        .collect()
 }
 
 fn find_reflection(pat: &[SmallVec<[char; 32]>]) -> usize {
@@ -25,30 +42,25 @@ fn find_reflection(pat: &[SmallVec<[char; 32]>]) -> usize {
 
 #[aoc(day13, part1)]
 fn part1(input: &ParsedInput) -> i64 {
-    let mut res = 0;
-
-    for pat in input {
-        res += match find_reflection(pat) as i64 {
-            0 => {
-                let mut rotated: SmallVec<[SmallVec<[char; 32]>; 32]> = SmallVec::new();
-                for i in 0..pat.first().unwrap().len() {
-                    rotated.push(pat.iter().map(|l| l[i]).collect::<SmallVec<_>>());
-                }
-
-                find_reflection(&rotated) as i64 * 100
+    parallel::map(input, |pat| match find_reflection(pat) as i64 {
+        0 => {
+            let mut rotated: SmallVec<[SmallVec<[char; 32]>; 32]> = SmallVec::new();
+            for i in 0..pat.first().unwrap().len() {
+                rotated.push(pat.iter().map(|l| l[i]).collect::<SmallVec<_>>());
             }
-            n => n,
-        }
-    }
 
-    res
+            find_reflection(&rotated) as i64 * 100
+        }
+        n => n,
+    })
+    .iter()
+    .sum()
 }
 
 #[aoc(day13, part2)]
 fn part2(input: &ParsedInput) -> i64 {
-    let mut res = 0;
-
-    for pat in input {
+    let res = parallel::map(input, |pat| {
+        let mut res = 0;
         let mut smudged = vec![];
 
         for y in 0..pat.len() {
@@ -91,15 +103,17 @@ fn part2(input: &ParsedInput) -> i64 {
                 }
             }
         }
-    }
+
+        res
+    })
+    .iter()
+    .sum::<i64>();
 
     res / 2
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     const TESTCASE: &str = r#"#.##..##.
 ..#.##.#.
 ##......#
@@ -115,13 +129,8 @@ mod tests {
 #####.##.
 ..##..###
 #....#..#"#;
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 405);
-    }
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse(TESTCASE)), 400);
+    examples! {
+        TESTCASE => part1: 405, part2: 400;
     }
 }
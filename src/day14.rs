@@ -1,17 +1,28 @@
-use std::collections::HashSet;
+use std::error::Error;
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
 use smallvec::SmallVec;
 
+use crate::{
+    assumptions::Assumption,
+    hash::{FxHashMap, FxHashSet},
+    progress,
+};
+
 type ParsedInput = SmallVec<[SmallVec<[char; 128]>; 128]>;
 
 #[aoc_generator(day14)]
-fn parse(input: &str) -> ParsedInput {
-    input
+fn parse(input: &str) -> Result<ParsedInput, Box<dyn Error>> {
+    let platform = input
         .lines()
-        .map(|l| l.chars().collect::<_>())
-        .collect::<_>()
+        .map(|l| l.chars().collect::<SmallVec<_>>())
+        .collect::<ParsedInput>();
+    let width = platform.first().map_or(0, |l| l.len());
+    if width == 0 || platform.iter().any(|l| l.len() != width) {
+        return Err("The platform is empty or not rectangular".into());
+    }
+    Ok(platform)
 }
 
 #[aoc(day14, part1)]
@@ -31,7 +42,10 @@ fn part1(input: &ParsedInput) -> i64 {
         }
     }
 
-    // Calculate the load
+    load(&input)
+}
+
+fn load(input: &ParsedInput) -> i64 {
     let mut res = 0;
     for (y, l) in input.iter().enumerate() {
         for c in l {
@@ -110,8 +124,10 @@ fn cycle(mut input: ParsedInput) -> ParsedInput {
 fn part2(input: &ParsedInput) -> i64 {
     let mut input = input.clone();
     // Roll
-    let mut seen = HashSet::new();
-    for _ in 0..1000000000 {
+    let mut seen = FxHashSet::default();
+    for i in 0..1000000000 {
+        // Stops at the first repeated state, which is nowhere near the billion
+        progress::report("spin cycles", i, 0);
         // Discovered by manually analyzing outputs.
         // I have no clue how it works, but it found the correct answer on test and on my input data.
         // Obviously there is a cycle in states, and the answer should be at something like
@@ -119,6 +135,7 @@ fn part2(input: &ParsedInput) -> i64 {
         //
         // The correct answer is 4 cycles before and 3 cycles after the first repeating state.
         if seen.contains(&input) {
+            debug!(cycles = seen.len(), "state repeats");
             input = cycle(input);
             input = cycle(input);
             input = cycle(input);
@@ -130,23 +147,40 @@ fn part2(input: &ParsedInput) -> i64 {
         input = cycle(input);
     }
 
-    // Calculate the load
-    let mut res = 0;
-    for (y, l) in input.iter().enumerate() {
-        for c in l {
-            if *c == 'O' {
-                res += input.len() - y;
-            }
-        }
+    load(&input)
+}
+
+pub(crate) static ASSUMPTIONS: &[Assumption] = &[Assumption {
+    part: 2,
+    description: "3 cycles after the first repeated state has the same load as a billion cycles",
+    check: cycle_shortcut_holds,
+}];
+
+/// Compares the shortcut taken by part 2 with the state the cycle actually ends up in
+fn cycle_shortcut_holds(input: &str) -> Result<(), String> {
+    let mut input = parse(input).map_err(|e| e.to_string())?;
+    let mut seen = FxHashMap::default();
+    let mut history = vec![];
+    while !seen.contains_key(&input) {
+        seen.insert(input.clone(), history.len());
+        history.push(input.clone());
+        input = cycle(input);
     }
 
-    res as i64
+    let start = seen[&input];
+    let period = history.len() - start;
+    let exact = load(&history[start + (1_000_000_000 - start) % period]);
+    let shortcut = load(&cycle(cycle(cycle(input))));
+    if shortcut != exact {
+        return Err(format!(
+            "The shortcut gives a load of {shortcut}, but a billion cycles give {exact}"
+        ));
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     const TESTCASE: &str = r#"O....#....
 O.OO#....#
 .....##...
@@ -157,13 +191,8 @@ O.#..O.#.#
 .......O..
 #....###..
 #OO..#...."#;
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 136);
-    }
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse(TESTCASE)), 64);
+    examples! {
+        TESTCASE => part1: 136, part2: 64;
     }
 }
@@ -1,20 +1,17 @@
-use std::str::from_utf8;
+use std::{borrow::Cow, error::Error, str::from_utf8};
 
-use aoc_runner_derive::{aoc, aoc_generator};
 use regex::bytes::Regex;
-use smallvec::SmallVec;
 
-type ParsedInput = Vec<SmallVec<[u8; 16]>>;
+use crate::input::Record;
 
-#[aoc_generator(day15)]
-fn parse(input: &str) -> ParsedInput {
-    input
+/// The steps of the first line, borrowed from the input
+pub(crate) fn parse(input: &str) -> Result<Vec<&str>, Box<dyn Error>> {
+    Ok(input
         .lines()
         .next()
-        .unwrap()
+        .ok_or("Empty input")?
         .split(',')
-        .map(|s| s.chars().map(|c| c as u8).collect::<_>())
-        .collect::<_>()
+        .collect())
 }
 
 fn hash(input: &[u8]) -> u64 {
@@ -27,51 +24,48 @@ fn hash(input: &[u8]) -> u64 {
     cur
 }
 
-#[aoc(day15, part1)]
-fn part1(input: &ParsedInput) -> i64 {
-    let mut res = 0;
-
-    for step in input {
-        res += hash(step);
-    }
-
-    res as i64
+pub(crate) fn part1(steps: &[&str]) -> Result<i64, Box<dyn Error>> {
+    part1_steps(steps.iter().map(|s| Ok(Cow::Borrowed(*s))))
 }
 
-#[aoc(day15, part2)]
-fn part2(input: &ParsedInput) -> usize {
-    let mut res = 0;
+/// Lenses in their boxes, by label. The labels borrow from the steps when those are borrowed.
+struct Boxes<'a>(Vec<Vec<(Cow<'a, [u8]>, u8)>>);
 
-    let mut boxes: Vec<Vec<(&[u8], u8)>> = Vec::with_capacity(256);
-    for _ in 0..256 {
-        boxes.push(Vec::new());
+impl<'a> Boxes<'a> {
+    fn new() -> Self {
+        Self(vec![Vec::new(); 256])
     }
 
-    let re = Regex::new(r"([a-zA-Z]+)([=-])(\d+)?").unwrap();
-    for step in input {
-        let capture = re.captures(step).unwrap();
+    fn apply(&mut self, re: &Regex, step: Cow<'a, [u8]>) -> Result<(), Box<dyn Error>> {
+        let invalid = || format!("Invalid step: {}", String::from_utf8_lossy(&step));
+        let capture = re.captures(&step).ok_or_else(invalid)?;
         match (
-            capture.get(1).map(|m| m.as_bytes()),
+            capture.get(1).map(|m| (m.range(), m.as_bytes())),
             capture.get(2).map(|m| m.as_bytes()[0]),
             capture
                 .get(3)
-                .map(|m| from_utf8(m.as_bytes()).unwrap().parse::<u8>().unwrap()),
+                .map(|m| {
+                    from_utf8(m.as_bytes())?
+                        .parse::<u8>()
+                        .map_err(Box::<dyn Error>::from)
+                })
+                .transpose()?,
         ) {
-            (Some(lbl), Some(b'-'), None) => {
-                let bx = &mut boxes[hash(lbl) as usize];
+            (Some((_, lbl)), Some(b'-'), None) => {
+                let bx = &mut self.0[hash(lbl) as usize];
                 for (i, lens) in bx.iter_mut().enumerate() {
-                    if lens.0 == lbl {
+                    if *lens.0 == *lbl {
                         bx.remove(i);
                         break;
                     }
                 }
             }
-            (Some(lbl), Some(b'='), Some(val)) => {
-                let bx = &mut boxes[hash(lbl) as usize];
+            (Some((range, lbl)), Some(b'='), Some(val)) => {
+                let bx = &mut self.0[hash(lbl) as usize];
 
                 let mut found = false;
                 for lens in bx.iter_mut() {
-                    if lens.0 == lbl {
+                    if *lens.0 == *lbl {
                         lens.1 = val;
                         found = true;
                         break;
@@ -79,34 +73,74 @@ fn part2(input: &ParsedInput) -> usize {
                 }
 
                 if !found {
+                    let lbl = match &step {
+                        Cow::Borrowed(step) => Cow::Borrowed(&step[range]),
+                        Cow::Owned(step) => Cow::Owned(step[range].to_vec()),
+                    };
                     bx.push((lbl, val));
                 }
             }
-            _ => unreachable!(),
+            _ => return Err(invalid().into()),
+        }
+
+        Ok(())
+    }
+
+    fn focusing_power(&self) -> usize {
+        let mut res = 0;
+        for (bx_idx, bx) in self.0.iter().enumerate() {
+            for (lens_idx, (_, val)) in bx.iter().enumerate() {
+                res += (bx_idx + 1) * (lens_idx + 1) * *val as usize;
+            }
         }
+        res
     }
+}
+
+fn step_regex() -> Regex {
+    Regex::new(r"([a-zA-Z]+)([=-])(\d+)?").unwrap()
+}
+
+pub(crate) fn part2(steps: &[&str]) -> Result<usize, Box<dyn Error>> {
+    part2_steps(steps.iter().map(|s| Ok(Cow::Borrowed(*s))))
+}
 
-    for (bx_idx, bx) in boxes.iter().enumerate() {
-        for (lens_idx, (_, val)) in bx.iter().enumerate() {
-            res += (bx_idx + 1) * (lens_idx + 1) * *val as usize;
+/// The step without the newline that ends the input
+fn trim(step: Cow<'_, str>) -> Cow<'_, [u8]> {
+    match step {
+        Cow::Borrowed(s) => Cow::Borrowed(s.trim_end_matches(['\n', '\r']).as_bytes()),
+        Cow::Owned(mut s) => {
+            s.truncate(s.trim_end_matches(['\n', '\r']).len());
+            Cow::Owned(s.into_bytes())
         }
     }
+}
+
+/// Part 1 on the comma separated steps
+pub(crate) fn part1_steps<'a>(
+    steps: impl Iterator<Item = Record<'a>>,
+) -> Result<i64, Box<dyn Error>> {
+    steps.map(|s| Ok(hash(&trim(s?)) as i64)).sum()
+}
+
+/// Part 2 on the comma separated steps
+pub(crate) fn part2_steps<'a>(
+    steps: impl Iterator<Item = Record<'a>>,
+) -> Result<usize, Box<dyn Error>> {
+    let mut boxes = Boxes::new();
+    let re = step_regex();
+    for step in steps {
+        boxes.apply(&re, trim(step?))?;
+    }
 
-    res
+    Ok(boxes.focusing_power())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     const TESTCASE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 1320);
-    }
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse(TESTCASE)), 145);
+    examples! {
+        TESTCASE => part1: 1320, part2: 145;
     }
 }
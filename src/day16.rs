@@ -1,169 +1,149 @@
-use std::collections::HashSet;
+use std::error::Error;
 
 use aoc_runner_derive::{aoc, aoc_generator};
-use cached::proc_macro::cached;
-use smallvec::SmallVec;
+use smallvec::{smallvec, SmallVec};
+
+use crate::{graph, hash::FxHashSet, parallel};
 
 type ParsedInput = SmallVec<[SmallVec<[char; 128]>; 128]>;
 
 type PosDir = ((i16, i16), (i16, i16));
 
 #[aoc_generator(day16)]
-fn parse(input: &str) -> ParsedInput {
-    input
+fn parse(input: &str) -> Result<ParsedInput, Box<dyn Error>> {
+    let map = input
         .lines()
-        .map(|l| l.chars().collect::<_>())
-        .collect::<_>()
+        .map(|l| l.chars().collect::<SmallVec<_>>())
+        .collect::<ParsedInput>();
+    let width = map.first().map_or(0, |l| l.len());
+    if width == 0 || map.iter().any(|l| l.len() != width) {
+        return Err("The map is empty or not rectangular".into());
+    }
+    if let Some(c) = map.iter().flatten().find(|c| !".|-/\\".contains(**c)) {
+        return Err(format!("Invalid tile: {c}").into());
+    }
+    Ok(map)
 }
 
-fn beam(
-    (x, y): (i16, i16),
-    (dx, dy): (i16, i16),
-    visited: &mut HashSet<PosDir>,
-    map: &ParsedInput,
-) {
-    if !visited.contains(&((x, y), (dx, dy))) {
-        visited.insert(((x, y), (dx, dy)));
-        if let Some(l) = map.get((y + dy) as usize) {
-            if let Some(c) = l.get((x + dx) as usize) {
-                match (c, dx, dy) {
-                    ('.', _, _) => beam((x + dx, y + dy), (dx, dy), visited, map),
-
-                    ('/', 1, 0) => beam((x + dx, y + dy), (0, -1), visited, map),
-                    ('/', -1, 0) => beam((x + dx, y + dy), (0, 1), visited, map),
-                    ('/', 0, 1) => beam((x + dx, y + dy), (-1, 0), visited, map),
-                    ('/', 0, -1) => beam((x + dx, y + dy), (1, 0), visited, map),
-
-                    ('\\', 1, 0) => beam((x + dx, y + dy), (0, 1), visited, map),
-                    ('\\', -1, 0) => beam((x + dx, y + dy), (0, -1), visited, map),
-                    ('\\', 0, 1) => beam((x + dx, y + dy), (1, 0), visited, map),
-                    ('\\', 0, -1) => beam((x + dx, y + dy), (-1, 0), visited, map),
-
-                    ('|', 1 | -1, 0) => {
-                        beam((x + dx, y + dy), (0, 1), visited, map);
-                        beam((x + dx, y + dy), (0, -1), visited, map)
-                    }
-                    ('|', 0, 1 | -1) => beam((x + dx, y + dy), (0, dy), visited, map),
-
-                    ('-', 1 | -1, 0) => beam((x + dx, y + dy), (dx, 0), visited, map),
-                    ('-', 0, 1 | -1) => {
-                        beam((x + dx, y + dy), (1, 0), visited, map);
-                        beam((x + dx, y + dy), (-1, 0), visited, map)
-                    }
-                    e => unreachable!("{e:?}"),
-                }
-            }
-        }
+/// Where a beam on `(x, y)` heading `(dx, dy)` goes next, none once it leaves the map
+fn next_beams(&((x, y), (dx, dy)): &PosDir, map: &ParsedInput) -> SmallVec<[PosDir; 2]> {
+    let Some(c) = map
+        .get((y + dy) as usize)
+        .and_then(|l| l.get((x + dx) as usize))
+    else {
+        return smallvec![];
+    };
+
+    let pos = (x + dx, y + dy);
+    match (c, dx, dy) {
+        ('.', _, _) => smallvec![(pos, (dx, dy))],
+
+        ('/', 1, 0) => smallvec![(pos, (0, -1))],
+        ('/', -1, 0) => smallvec![(pos, (0, 1))],
+        ('/', 0, 1) => smallvec![(pos, (-1, 0))],
+        ('/', 0, -1) => smallvec![(pos, (1, 0))],
+
+        ('\\', 1, 0) => smallvec![(pos, (0, 1))],
+        ('\\', -1, 0) => smallvec![(pos, (0, -1))],
+        ('\\', 0, 1) => smallvec![(pos, (1, 0))],
+        ('\\', 0, -1) => smallvec![(pos, (-1, 0))],
+
+        ('|', 1 | -1, 0) => smallvec![(pos, (0, 1)), (pos, (0, -1))],
+        ('|', 0, 1 | -1) => smallvec![(pos, (0, dy))],
+
+        ('-', 1 | -1, 0) => smallvec![(pos, (dx, 0))],
+        ('-', 0, 1 | -1) => smallvec![(pos, (1, 0)), (pos, (-1, 0))],
+        e => unreachable!("{e:?}"),
     }
 }
 
-#[aoc(day16, part1)]
-fn part1(input: &ParsedInput) -> usize {
-    let mut visited = HashSet::new();
-
-    beam((-1, 0), (1, 0), &mut visited, input);
+/// Number of tiles energized by a beam entering the map from `start`, which is off the map
+fn energized(start: PosDir, map: &ParsedInput) -> usize {
+    let visited = graph::reachable([start], |b| next_beams(b, map));
     let visited = visited
         .iter()
         .map(|(coord, _)| coord)
-        .collect::<HashSet<_>>();
+        .collect::<FxHashSet<_>>();
 
     visited.len() - 1
 }
 
-#[aoc(day16, part2)]
-fn part2(input: &ParsedInput) -> usize {
-    let max_x = input[0].len() as i16;
-    let max_y = input.len() as i16;
+#[aoc(day16, part1)]
+fn part1(input: &ParsedInput) -> usize {
+    energized(((-1, 0), (1, 0)), input)
+}
 
-    let mut tiles = vec![];
-    for x in 0..max_x {
-        let mut visited = HashSet::new();
-        beam((x, -1), (0, 1), &mut visited, input);
-        let visited = visited
-            .iter()
-            .map(|(coord, _)| coord)
-            .collect::<HashSet<_>>();
-        tiles.push(visited.len() - 1);
+/// Every tile on the edge of the map, with the beam entering it from outside
+fn edge_starts(map: &ParsedInput) -> Vec<PosDir> {
+    let max_x = map[0].len() as i16;
+    let max_y = map.len() as i16;
 
-        let mut visited = HashSet::new();
-        beam((x, max_y), (0, -1), &mut visited, input);
-        let visited = visited
-            .iter()
-            .map(|(coord, _)| coord)
-            .collect::<HashSet<_>>();
-        tiles.push(visited.len() - 1);
+    let mut starts = vec![];
+    for x in 0..max_x {
+        starts.push(((x, -1), (0, 1)));
+        starts.push(((x, max_y), (0, -1)));
     }
-
     for y in 0..max_y {
-        let mut visited = HashSet::new();
-        beam((-1, y), (1, 0), &mut visited, input);
-        let visited = visited
-            .iter()
-            .map(|(coord, _)| coord)
-            .collect::<HashSet<_>>();
-        tiles.push(visited.len() - 1);
-
-        let mut visited = HashSet::new();
-        beam((max_x, y), (-1, 0), &mut visited, input);
-        let visited = visited
-            .iter()
-            .map(|(coord, _)| coord)
-            .collect::<HashSet<_>>();
-        tiles.push(visited.len() - 1);
+        starts.push(((-1, y), (1, 0)));
+        starts.push(((max_x, y), (-1, 0)));
     }
+    starts
+}
+
+#[aoc(day16, part2)]
+fn part2(input: &ParsedInput) -> usize {
+    let tiles = parallel::map(&edge_starts(input), |&start| energized(start, input));
 
     *tiles.iter().reduce(|acc, i| acc.max(i)).unwrap()
 }
 
-// TODO: overflows on input, works on test
-// #[cached(
-//     key = "(i16, i16, i16, i16, Vec<PosDir>)",
-//     convert = r#"{ (x, y, dx, dy, visited.iter().copied().collect::<Vec<_>>()) }"#
-// )]
-fn beam_memoized(
+// Follows the beam by recursion, without a cache: the result depends on the whole `visited` set,
+// so a cache has to be keyed on it and almost never hits. The old global `#[cached]` version only
+// grew until it overflowed.
+fn beam_recursive(
     (x, y): (i16, i16),
     (dx, dy): (i16, i16),
-    mut visited: HashSet<PosDir>,
+    mut visited: FxHashSet<PosDir>,
     map: &ParsedInput,
-) -> HashSet<PosDir> {
-    let mut res = HashSet::new();
+) -> FxHashSet<PosDir> {
+    let mut res = FxHashSet::default();
     if !visited.contains(&((x, y), (dx, dy))) {
         visited.insert(((x, y), (dx, dy)));
         res.insert(((x, y), (dx, dy)));
         if let Some(l) = map.get((y + dy) as usize) {
             if let Some(c) = l.get((x + dx) as usize) {
                 res.extend(match (c, dx, dy) {
-                    ('.', _, _) => beam_memoized((x + dx, y + dy), (dx, dy), visited, map),
+                    ('.', _, _) => beam_recursive((x + dx, y + dy), (dx, dy), visited, map),
 
-                    ('/', 1, 0) => beam_memoized((x + dx, y + dy), (0, -1), visited, map),
-                    ('/', -1, 0) => beam_memoized((x + dx, y + dy), (0, 1), visited, map),
-                    ('/', 0, 1) => beam_memoized((x + dx, y + dy), (-1, 0), visited, map),
-                    ('/', 0, -1) => beam_memoized((x + dx, y + dy), (1, 0), visited, map),
+                    ('/', 1, 0) => beam_recursive((x + dx, y + dy), (0, -1), visited, map),
+                    ('/', -1, 0) => beam_recursive((x + dx, y + dy), (0, 1), visited, map),
+                    ('/', 0, 1) => beam_recursive((x + dx, y + dy), (-1, 0), visited, map),
+                    ('/', 0, -1) => beam_recursive((x + dx, y + dy), (1, 0), visited, map),
 
-                    ('\\', 1, 0) => beam_memoized((x + dx, y + dy), (0, 1), visited, map),
-                    ('\\', -1, 0) => beam_memoized((x + dx, y + dy), (0, -1), visited, map),
-                    ('\\', 0, 1) => beam_memoized((x + dx, y + dy), (1, 0), visited, map),
-                    ('\\', 0, -1) => beam_memoized((x + dx, y + dy), (-1, 0), visited, map),
+                    ('\\', 1, 0) => beam_recursive((x + dx, y + dy), (0, 1), visited, map),
+                    ('\\', -1, 0) => beam_recursive((x + dx, y + dy), (0, -1), visited, map),
+                    ('\\', 0, 1) => beam_recursive((x + dx, y + dy), (1, 0), visited, map),
+                    ('\\', 0, -1) => beam_recursive((x + dx, y + dy), (-1, 0), visited, map),
 
                     ('|', 1 | -1, 0) => {
-                        let mut a = beam_memoized((x + dx, y + dy), (0, 1), visited.clone(), map);
-                        a.extend(beam_memoized(
+                        let mut a = beam_recursive((x + dx, y + dy), (0, 1), visited.clone(), map);
+                        a.extend(beam_recursive(
                             (x + dx, y + dy),
                             (0, -1),
-                            visited,//.union(&a).copied().collect::<HashSet<_>>(),
+                            visited, //.union(&a).copied().collect::<HashSet<_>>(),
                             map,
                         ));
                         a
                     }
-                    ('|', 0, 1 | -1) => beam_memoized((x + dx, y + dy), (0, dy), visited, map),
+                    ('|', 0, 1 | -1) => beam_recursive((x + dx, y + dy), (0, dy), visited, map),
 
-                    ('-', 1 | -1, 0) => beam_memoized((x + dx, y + dy), (dx, 0), visited, map),
+                    ('-', 1 | -1, 0) => beam_recursive((x + dx, y + dy), (dx, 0), visited, map),
                     ('-', 0, 1 | -1) => {
-                        let mut a = beam_memoized((x + dx, y + dy), (1, 0), visited.clone(), map);
-                        a.extend(beam_memoized(
+                        let mut a = beam_recursive((x + dx, y + dy), (1, 0), visited.clone(), map);
+                        a.extend(beam_recursive(
                             (x + dx, y + dy),
                             (-1, 0),
-                            visited,//.union(&a).copied().collect::<HashSet<_>>(),
+                            visited, //.union(&a).copied().collect::<HashSet<_>>(),
                             map,
                         ));
                         a
@@ -177,62 +157,33 @@ fn beam_memoized(
     res
 }
 
-#[aoc(day16, part1, memoized)]
-fn part1_memoized(input: &ParsedInput) -> usize {
-    let visited = beam_memoized((-1, 0), (1, 0), HashSet::new(), input)
+#[aoc(day16, part1, recursive)]
+fn part1_recursive(input: &ParsedInput) -> usize {
+    let visited = beam_recursive((-1, 0), (1, 0), FxHashSet::default(), input)
         .iter()
         .map(|(coord, _)| coord)
         .copied()
-        .collect::<HashSet<_>>();
+        .collect::<FxHashSet<_>>();
 
     visited.len() - 1
 }
 
-#[aoc(day16, part2, memoized)]
-fn part2_memoized(input: &ParsedInput) -> usize {
-    let max_x = input[0].len() as i16;
-    let max_y = input.len() as i16;
-
-    let mut tiles = vec![];
-    for x in 0..max_x {
-        let visited = beam_memoized((x, -1), (0, 1), HashSet::new(), input)
-            .iter()
-            .map(|(coord, _)| coord)
-            .copied()
-            .collect::<HashSet<_>>();
-        tiles.push(visited.len() - 1);
-
-        let visited = beam_memoized((x, max_y), (0, -1), HashSet::new(), input)
+#[aoc(day16, part2, recursive)]
+fn part2_recursive(input: &ParsedInput) -> usize {
+    let tiles = parallel::map(&edge_starts(input), |&(pos, dir)| {
+        let visited = beam_recursive(pos, dir, FxHashSet::default(), input)
             .iter()
             .map(|(coord, _)| coord)
             .copied()
-            .collect::<HashSet<_>>();
-        tiles.push(visited.len() - 1);
-    }
-
-    for y in 0..max_y {
-        let visited = beam_memoized((-1, y), (1, 0), HashSet::new(), input)
-            .iter()
-            .map(|(coord, _)| coord)
-            .copied()
-            .collect::<HashSet<_>>();
-        tiles.push(visited.len() - 1);
-
-        let visited = beam_memoized((max_x, y), (-1, 0), HashSet::new(), input)
-            .iter()
-            .map(|(coord, _)| coord)
-            .copied()
-            .collect::<HashSet<_>>();
-        tiles.push(visited.len() - 1);
-    }
+            .collect::<FxHashSet<_>>();
+        visited.len() - 1
+    });
 
     *tiles.iter().reduce(|acc, i| acc.max(i)).unwrap()
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     const TESTCASE: &str = r".|...\....
 |.-.\.....
 .....|-...
@@ -243,23 +194,8 @@ mod tests {
 .-.-/..|..
 .|....-|.\
 ..//.|....";
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 46);
-    }
-
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse(TESTCASE)), 51);
-    }
-
-    #[test]
-    fn part1_memoized_example() {
-        assert_eq!(part1_memoized(&parse(TESTCASE)), 46);
-    }
 
-    #[test]
-    fn part2_memoized_example() {
-        assert_eq!(part2_memoized(&parse(TESTCASE)), 51);
+    examples! {
+        TESTCASE => part1: 46, part2: 51;
     }
 }
@@ -1,9 +1,14 @@
-use std::collections::HashSet;
+use std::error::Error;
 
 use aoc_runner_derive::{aoc, aoc_generator};
-use pathfinding::prelude::astar;
 use smallvec::{smallvec, SmallVec};
 
+use crate::{
+    assumptions::Assumption,
+    dot::Graph,
+    graph::{self, astar},
+};
+
 type ParsedInput = Vec<Vec<u32>>;
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord)]
@@ -128,62 +133,138 @@ impl Node {
                 self.heat_loss_to(self.0, self.1 + 4, map),
             ));
         }
-        // println!("{},{} => {res:?}", self.0, self.1);
+        trace!(x = self.0, y = self.1, ?res, "successors");
 
         res
     }
 }
 
 #[aoc_generator(day17)]
-fn parse(input: &str) -> ParsedInput {
-    input
+fn parse(input: &str) -> Result<ParsedInput, Box<dyn Error>> {
+    let map = input
         .lines()
-        .map(|l| l.chars().map(|c| c.to_digit(10).unwrap()).collect::<_>())
-        .collect::<_>()
+        .map(|l| {
+            l.chars()
+                .map(|c| c.to_digit(10).ok_or(format!("Invalid heat loss: {c}")))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<ParsedInput, _>>()?;
+    let width = map.first().map_or(0, |l| l.len());
+    if width == 0 || map.iter().any(|l| l.len() != width) {
+        return Err("The map is empty or not rectangular".into());
+    }
+    Ok(map)
 }
 
 #[aoc(day17, part1)]
-fn part1(input: &ParsedInput) -> u32 {
+fn part1(input: &ParsedInput) -> Result<u32, Box<dyn Error>> {
+    is_square(input)?;
     let path = astar(
-        &Node(0, 0, smallvec![(0, 0); 3]),
+        Node(0, 0, smallvec![(0, 0); 3]),
         |n| n.successors(input, input.len()),
         |c| c.0.abs_diff(input.len() as i32) + c.1.abs_diff(input.len() as i32),
         |p| p.0 == input.len() as i32 - 1 && p.1 == input.len() as i32 - 1,
     )
-    .unwrap();
+    .ok_or("No path to the factory")?;
 
-    path.1
+    Ok(path.1)
 }
 
 #[aoc(day17, part2)]
-fn part2(input: &ParsedInput) -> u32 {
+fn part2(input: &ParsedInput) -> Result<u32, Box<dyn Error>> {
     let path = astar(
-        &Node(0, 0, smallvec![(0, 0); 7]),
+        Node(0, 0, smallvec![(0, 0); 7]),
         |n| n.successors_p2(input),
         |c| c.0.abs_diff(input.len() as i32) + c.1.abs_diff(input.len() as i32),
         |p| p.0 == input[0].len() as i32 - 1 && p.1 == input.len() as i32 - 1,
     )
-    .unwrap();
-    let mut v = HashSet::new();
-    v.extend(path.0.iter().map(|n| (n.0, n.1)));
-    // for (y, l) in input.iter().enumerate() {
-    //     for (x, n) in l.iter().enumerate() {
-    //         if v.contains(&(x as i32, y as i32)) {
-    //             print!("X");
-    //         } else {
-    //             print!("{n}");
-    //         }
-    //     }
-    //     println!();
-    // }
-    // println!("{:?}", path.0);
-    path.1
+    .ok_or("No path to the factory")?;
+    debug!(
+        path = ?path.0.iter().map(|n| (n.0, n.1)).collect::<Vec<_>>(),
+        heat_loss = path.1,
+        "cheapest path"
+    );
+    Ok(path.1)
+}
+
+pub(crate) static ASSUMPTIONS: &[Assumption] = &[Assumption {
+    part: 1,
+    description: "The grid is square",
+    check: |input| is_square(&parse(input).map_err(|e| e.to_string())?),
+}];
+
+/// Part 1 uses the height of the grid for both axes
+fn is_square(input: &ParsedInput) -> Result<(), String> {
+    let (w, h) = (input[0].len(), input.len());
+    if w != h {
+        return Err(format!("The grid is {w}x{h}"));
+    }
+    Ok(())
+}
+
+/// The part 1 search states whose tile is at most `radius` steps from the start, as a Graphviz
+/// graph. Edges carry the heat loss of the move, the states on the best path are red.
+pub(crate) fn dot(input: &str, radius: usize) -> Result<String, Box<dyn Error>> {
+    let map = parse(input)?;
+    is_square(&map)?;
+    let near = |n: &Node| (n.0 + n.1) as usize <= radius;
+    let start = Node(0, 0, smallvec![(0, 0); 3]);
+    let best = astar(
+        start.clone(),
+        |n| n.successors(&map, map.len()),
+        |c| c.0.abs_diff(map.len() as i32) + c.1.abs_diff(map.len() as i32),
+        |p| p.0 == map.len() as i32 - 1 && p.1 == map.len() as i32 - 1,
+    )
+    .map_or_else(Vec::new, |p| p.0);
+
+    let mut states = graph::reachable([start.clone()], |n| {
+        n.successors(&map, map.len())
+            .into_iter()
+            .map(|(n, _)| n)
+            .filter(near)
+            .collect::<Vec<_>>()
+    })
+    .into_iter()
+    .collect::<Vec<_>>();
+    states.sort_unstable();
+
+    let id = |n: &Node| {
+        let moves =
+            n.2.iter()
+                .map(|d| match d {
+                    (1, 0) => '>',
+                    (-1, 0) => '<',
+                    (0, -1) => '^',
+                    (0, 1) => 'v',
+                    _ => '.',
+                })
+                .collect::<String>();
+        format!("({},{})\n{moves}", n.0, n.1)
+    };
+
+    let mut g = Graph::digraph("day17");
+    for n in &states {
+        let mut attrs = vec![];
+        if *n == start {
+            attrs.push(("shape", "box"));
+        }
+        if best.contains(n) {
+            attrs.extend([("color", "red"), ("penwidth", "2")]);
+        }
+        g.node(&id(n), &attrs);
+    }
+    for n in &states {
+        for (next, cost) in n.successors(&map, map.len()) {
+            if near(&next) {
+                g.edge(&id(n), &id(&next), &[("label", &cost.to_string())]);
+            }
+        }
+    }
+    Ok(g.render())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     const TESTCASE: &str = r#"2413432311323
 3215453535623
 3255245654254
@@ -197,27 +278,18 @@ mod tests {
 1224686865563
 2546548887735
 4322674655533"#;
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 102);
-    }
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse(TESTCASE)), 94);
-    }
-
-    #[test]
-    fn part2_example2() {
-        assert_eq!(
-            part2(&parse(
-                r"111111111111
+    examples! {
+        TESTCASE => part1: 102, part2: 94;
+        r"111111111111
 999999999991
 999999999991
 999999999991
-999999999991"
-            )),
-            71
-        );
+999999999991" => part2: 71;
+    }
+
+    #[test]
+    fn dot() {
+        crate::dot::assert_golden("day17.dot", &super::dot(TESTCASE, 2).unwrap());
     }
 }
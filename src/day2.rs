@@ -1,38 +1,58 @@
+use std::error::Error;
+
 use aoc_runner_derive::{aoc, aoc_generator};
 
-type ParsedInput = Vec<String>;
+use crate::input::Record;
+
+/// Red, green and blue cubes shown at once
+type Set = [u64; 3];
+
+type ParsedInput = Vec<Vec<Set>>;
 
 #[aoc_generator(day2)]
-fn parse(input: &str) -> ParsedInput {
-    input.lines().map(String::from).collect::<Vec<_>>()
+fn parse(input: &str) -> Result<ParsedInput, Box<dyn Error>> {
+    input.lines().map(parse_game).collect()
+}
+
+fn parse_game(line: &str) -> Result<Vec<Set>, Box<dyn Error>> {
+    let (_, sets) = line.split_once(':').ok_or("Invalid string.")?;
+    sets.split(';').map(parse_set).collect()
+}
+
+fn parse_set(set: &str) -> Result<Set, Box<dyn Error>> {
+    let mut res = [0; 3];
+    for cubes in set.split(',') {
+        let mut s = cubes.split_ascii_whitespace();
+        let num = s.next().ok_or("Missing cube count")?.parse::<u32>()?;
+        let color = match s.next() {
+            Some("red") => 0,
+            Some("green") => 1,
+            Some("blue") => 2,
+            c => return Err(format!("Invalid color: {c:?}").into()),
+        };
+        res[color] += u64::from(num);
+    }
+    Ok(res)
+}
+
+fn is_possible(sets: &[Set]) -> bool {
+    sets.iter().all(|&[r, g, b]| r <= 12 && g <= 13 && b <= 14)
+}
+
+/// Power of the fewest cubes that make the game possible
+fn power(sets: &[Set]) -> Option<u64> {
+    let [r, g, b] = sets.iter().fold([0; 3], |[r, g, b], s| {
+        [r.max(s[0]), g.max(s[1]), b.max(s[2])]
+    });
+    r.checked_mul(g)?.checked_mul(b)
 }
 
 #[aoc(day2, part1)]
 fn part1(input: &ParsedInput) -> i64 {
     let mut res = 0;
 
-    for (id, l) in input.iter().enumerate() {
-        let l = l.split(':').nth(1).expect("Invalid string.");
-        if l.split(';').all(|set| {
-            let mut r = 0;
-            let mut g = 0;
-            let mut b = 0;
-            for cubes in set.split(',') {
-                let mut s = cubes.split_ascii_whitespace();
-                let num = s.next().unwrap().parse::<i32>().unwrap();
-                match s.next().unwrap() {
-                    "red" => r += num,
-                    "green" => g += num,
-                    "blue" => b += num,
-                    _ => unreachable!(),
-                }
-                if r > 12 || g > 13 || b > 14 {
-                    return false;
-                }
-            }
-
-            true
-        }) {
+    for (id, sets) in input.iter().enumerate() {
+        if is_possible(sets) {
             res += id + 1;
         }
     }
@@ -41,49 +61,55 @@ fn part1(input: &ParsedInput) -> i64 {
 }
 
 #[aoc(day2, part2)]
-fn part2(input: &ParsedInput) -> i64 {
-    let mut power = 0;
-
-    for l in input {
-        let mut max_r = 0;
-        let mut max_g = 0;
-        let mut max_b = 0;
-        let l = l.split(':').nth(1).expect("Invalid string.");
-        for set in l.split(';') {
-            for cubes in set.split(',') {
-                let mut s = cubes.split_ascii_whitespace();
-                let num = s.next().unwrap().parse::<i32>().unwrap();
-                match s.next().unwrap() {
-                    "red" => max_r = max_r.max(num),
-                    "green" => max_g = max_g.max(num),
-                    "blue" => max_b = max_b.max(num),
-                    _ => unreachable!(),
-                };
-            }
+fn part2(input: &ParsedInput) -> Result<u64, Box<dyn Error>> {
+    let mut power = 0u64;
+
+    for sets in input {
+        power = self::power(sets)
+            .and_then(|p| p.checked_add(power))
+            .ok_or("The power overflows")?;
+    }
+
+    Ok(power)
+}
+
+pub(crate) fn part1_lines<'a>(
+    lines: impl Iterator<Item = Record<'a>>,
+) -> Result<i64, Box<dyn Error>> {
+    let mut res = 0;
+
+    for (id, line) in lines.enumerate() {
+        if is_possible(&parse_game(&line?)?) {
+            res += id + 1;
         }
+    }
+
+    Ok(res as i64)
+}
 
-        power += max_r * max_g * max_b;
+pub(crate) fn part2_lines<'a>(
+    lines: impl Iterator<Item = Record<'a>>,
+) -> Result<u64, Box<dyn Error>> {
+    let mut power = 0u64;
+
+    for line in lines {
+        power = self::power(&parse_game(&line?)?)
+            .and_then(|p| p.checked_add(power))
+            .ok_or("The power overflows")?;
     }
 
-    power as i64
+    Ok(power)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     const TESTCASE: &str = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green"#;
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 8);
-    }
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse(TESTCASE)), 2286);
+    examples! {
+        TESTCASE => part1: 8, part2: 2286;
     }
 }
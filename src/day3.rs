@@ -1,10 +1,9 @@
-use std::{
-    collections::{HashMap, HashSet},
-    error::Error,
-};
+use std::error::Error;
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::hash::{FxHashMap, FxHashSet};
+
 type ParsedInput = Vec<Vec<char>>;
 
 #[aoc_generator(day3)]
@@ -26,7 +25,7 @@ const NEIGHBORS: &[(i32, i32)] = &[
     (1, 1),
 ];
 
-fn check_pos(i: usize, j: usize, lines: &Vec<Vec<char>>) -> bool {
+fn check_pos(i: usize, j: usize, lines: &[Vec<char>]) -> bool {
     for (di, dj) in NEIGHBORS {
         let ai = i as i32 + di;
         let aj = j as i32 + dj;
@@ -41,7 +40,7 @@ fn check_pos(i: usize, j: usize, lines: &Vec<Vec<char>>) -> bool {
 }
 
 #[aoc(day3, part1)]
-fn part1(input: &ParsedInput) -> Result<i64, Box<dyn Error>> {
+fn part1(input: &ParsedInput) -> Result<u64, Box<dyn Error>> {
     let mut res = 0;
     for (i, line) in input.iter().enumerate() {
         let mut valid = false;
@@ -54,7 +53,7 @@ fn part1(input: &ParsedInput) -> Result<i64, Box<dyn Error>> {
                 }
             } else {
                 if !cur.is_empty() && valid {
-                    res += cur.parse::<i32>()?;
+                    res += u64::from(cur.parse::<u32>()?);
                 }
                 cur.clear();
                 valid = false;
@@ -62,19 +61,19 @@ fn part1(input: &ParsedInput) -> Result<i64, Box<dyn Error>> {
         }
 
         if !cur.is_empty() && valid {
-            res += cur.parse::<i32>()?;
+            res += u64::from(cur.parse::<u32>()?);
         }
     }
 
-    Ok(res as i64)
+    Ok(res)
 }
 
 #[aoc(day3, part2)]
-fn part2(input: &ParsedInput) -> Result<i64, Box<dyn Error>> {
-    let mut gears: HashMap<(usize, usize), Vec<i32>> = HashMap::new();
+fn part2(input: &ParsedInput) -> Result<u64, Box<dyn Error>> {
+    let mut gears: FxHashMap<(usize, usize), Vec<u64>> = FxHashMap::default();
 
     for (i, line) in input.iter().enumerate() {
-        let mut adj_gears = HashSet::new();
+        let mut adj_gears = FxHashSet::default();
         let mut cur = String::new();
         for (j, char) in line.iter().enumerate() {
             if char.is_ascii_digit() {
@@ -84,7 +83,7 @@ fn part2(input: &ParsedInput) -> Result<i64, Box<dyn Error>> {
                 }
             } else {
                 if !cur.is_empty() {
-                    let num = cur.parse::<i32>()?;
+                    let num = u64::from(cur.parse::<u32>()?);
                     for g in &adj_gears {
                         gears
                             .entry(*g)
@@ -98,7 +97,7 @@ fn part2(input: &ParsedInput) -> Result<i64, Box<dyn Error>> {
         }
 
         if !cur.is_empty() {
-            let num = cur.parse::<i32>()?;
+            let num = u64::from(cur.parse::<u32>()?);
             for g in &adj_gears {
                 gears
                     .entry(*g)
@@ -108,14 +107,15 @@ fn part2(input: &ParsedInput) -> Result<i64, Box<dyn Error>> {
         }
     }
 
-    let res = gears.iter().fold(0, |acc, (_, g)| match g.len() {
-        2 => g.iter().product::<i32>() + acc,
-        _ => acc,
+    let res = gears.iter().try_fold(0u64, |acc, (_, g)| match g.len() {
+        // Two u32 never overflow a u64, but the sum can
+        2 => acc.checked_add(g[0] * g[1]),
+        _ => Some(acc),
     });
-    Ok(res as i64)
+    Ok(res.ok_or("The gear ratios overflow")?)
 }
 
-fn check_gear(i: usize, j: usize, lines: &Vec<Vec<char>>) -> Option<(usize, usize)> {
+fn check_gear(i: usize, j: usize, lines: &[Vec<char>]) -> Option<(usize, usize)> {
     for (di, dj) in NEIGHBORS {
         let ai = i as i32 + di;
         let aj = j as i32 + dj;
@@ -131,8 +131,6 @@ fn check_gear(i: usize, j: usize, lines: &Vec<Vec<char>>) -> Option<(usize, usiz
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     const TESTCASE: &str = r#"467..114..
 ...*......
 ..35..633.
@@ -143,13 +141,8 @@ mod tests {
 ......755.
 ...$.*....
 .664.598.."#;
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)).unwrap(), 4361);
-    }
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse(TESTCASE)).unwrap(), 467835);
+    examples! {
+        TESTCASE => part1: 4361, part2: 467835;
     }
 }
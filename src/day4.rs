@@ -1,102 +1,95 @@
-use std::{collections::HashSet, error::Error};
+use std::{
+    collections::{HashSet, VecDeque},
+    error::Error,
+};
 
-use aoc_runner_derive::{aoc, aoc_generator};
+use aoc_runner_derive::aoc;
 
-#[aoc_generator(day4)]
-fn parse(input: &str) -> Vec<String> {
-    input.lines().map(String::from).collect::<Vec<_>>()
+use crate::input::{self, Record};
+
+/// How many of the scratched numbers are winning ones
+fn matches(line: &str) -> Result<usize, Box<dyn Error>> {
+    let line = line.split(':').nth(1).ok_or("Invalid line.")?;
+    let mut split = line.split('|');
+    let scratched: HashSet<&str> = split
+        .next()
+        .ok_or("Invalid line.")?
+        .split_ascii_whitespace()
+        .collect();
+    let winning: HashSet<&str> = split
+        .next()
+        .ok_or("Invalid line.")?
+        .split_ascii_whitespace()
+        .collect();
+
+    Ok(scratched.intersection(&winning).count())
 }
 
 #[aoc(day4, part1)]
-fn part1(lines: &[String]) -> i64 {
-    let mut res = 0;
+fn part1(input: &str) -> Result<i64, Box<dyn Error>> {
+    part1_lines(input::str_lines(input))
+}
 
-    for line in lines {
-        let line = line.split(':').nth(1).unwrap();
-        let scratched: HashSet<&str> = line
-            .split('|')
-            .next()
-            .unwrap()
-            .split_ascii_whitespace()
-            .collect();
-        let winning: HashSet<&str> = line
-            .split('|')
-            .nth(1)
-            .unwrap()
-            .split_ascii_whitespace()
-            .collect();
+#[aoc(day4, part2)]
+fn part2(input: &str) -> Result<i64, Box<dyn Error>> {
+    part2_lines(input::str_lines(input))
+}
+
+pub(crate) fn part1_lines<'a>(
+    lines: impl Iterator<Item = Record<'a>>,
+) -> Result<i64, Box<dyn Error>> {
+    let mut res = 0i64;
 
-        let common = scratched.intersection(&winning).count();
+    for line in lines {
+        let common = matches(&line?)?;
         if common > 0 {
-            res += 2i32.pow(common as u32 - 1);
+            res = 2i64
+                .checked_pow(common as u32 - 1)
+                .and_then(|p| p.checked_add(res))
+                .ok_or("The points overflow")?;
         }
     }
 
-    res as i64
+    Ok(res)
 }
 
-#[aoc(day4, part2)]
-fn part2(lines: &[String]) -> Result<i64, Box<dyn Error>> {
-    let mut res = 0;
+pub(crate) fn part2_lines<'a>(
+    lines: impl Iterator<Item = Record<'a>>,
+) -> Result<i64, Box<dyn Error>> {
+    let mut res = 0i64;
 
-    let mut copies = vec![1; lines.len()];
-    for (id, line) in lines.iter().enumerate() {
-        let line = line.split(':').nth(1).ok_or("Invalid line.")?;
+    // Extra copies of the next cards, only as many as a card has numbers
+    let mut won = VecDeque::new();
+    for line in lines {
+        let common = matches(&line?)?;
+        let copies = won
+            .pop_front()
+            .unwrap_or(0i64)
+            .checked_add(1)
+            .ok_or("Too many copies")?;
+        res = copies.checked_add(res).ok_or("Too many copies")?;
 
-        let mut split = line.split('|');
-        let scratched: HashSet<&str> = split
-            .next()
-            .ok_or("Invalid line.")?
-            .split_ascii_whitespace()
-            .collect();
-        let winning: HashSet<&str> = split
-            .next()
-            .ok_or("Invalid line.")?
-            .split_ascii_whitespace()
-            .collect();
-        res += copies[id];
-        for i in 1..=scratched.intersection(&winning).count() {
-            if id + i < copies.len() {
-                copies[id + i] += copies[id];
-            }
+        if won.len() < common {
+            won.resize(common, 0);
+        }
+        for w in won.iter_mut().take(common) {
+            *w = w.checked_add(copies).ok_or("Too many copies")?;
         }
     }
 
-    Ok(res as i64)
+    Ok(res)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
-    #[test]
-    fn part1_example() {
-        assert_eq!(
-            part1(&parse(
-                "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+    const TESTCASE: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
         Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
         Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
         Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
         Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-        Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
-            )),
-            13
-        );
-    }
+        Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(
-            part2(&parse(
-                "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-        Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-        Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-        Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-        Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-        Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11"
-            ))
-            .unwrap(),
-            30
-        );
+    examples! {
+        TESTCASE => part1: 13, part2: 30;
     }
 }
@@ -1,20 +1,20 @@
-use std::ops::Range;
+use std::{error::Error, ops::Range};
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::parallel;
+
 type MapStruct = (Vec<i64>, Vec<Vec<(Range<i64>, i64, i64)>>);
 #[aoc_generator(day5)]
-fn parse(input: &str) -> MapStruct {
+fn parse(input: &str) -> Result<MapStruct, Box<dyn Error>> {
     let mut maps = input.split("\n\n");
     let seeds = maps
         .next()
-        .unwrap()
-        .split(':')
-        .nth(1)
-        .unwrap()
+        .and_then(|s| s.split(':').nth(1))
+        .ok_or("Missing seeds")?
         .split_ascii_whitespace()
-        .map(|n| n.parse::<i64>().unwrap())
-        .collect::<Vec<_>>();
+        .map(|n| n.parse::<i64>())
+        .collect::<Result<Vec<_>, _>>()?;
 
     let maps_parsed = maps
         .map(|map| {
@@ -22,26 +22,26 @@ fn parse(input: &str) -> MapStruct {
             let map = map.split('\n').skip(1);
 
             for mapping in map {
-                let mut nums = mapping
+                let nums = mapping
                     .split_ascii_whitespace()
-                    .map(|n| n.parse::<i64>().unwrap());
-
-                let dst = nums.next().unwrap();
-                let src = nums.next().unwrap();
-                let range = nums.next().unwrap();
-                out.push((src..(src + range), dst, range));
+                    .map(|n| n.parse::<i64>())
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                let &[dst, src, range] = nums.as_slice() else {
+                    return Err(format!("Invalid mapping: {mapping}").into());
+                };
+                let end = src.checked_add(range).ok_or("The range overflows")?;
+                out.push((src..end, dst, range));
             }
-            out
+            Ok(out)
         })
-        .collect::<Vec<_>>();
-    (seeds, maps_parsed)
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+    Ok((seeds, maps_parsed))
 }
 
 #[aoc(day5, part1)]
 fn part1((seeds, maps): &MapStruct) -> i64 {
-    let mut res = i64::MAX;
-
-    for seed in seeds {
+    parallel::map(seeds, |seed| {
         // Fuck me in the ass, borrow checker
         let mut seed = *seed;
         for map in maps {
@@ -49,10 +49,10 @@ fn part1((seeds, maps): &MapStruct) -> i64 {
                 seed = d + (seed - s.start);
             }
         }
-        res = res.min(seed);
-    }
-
-    res
+        seed
+    })
+    .into_iter()
+    .fold(i64::MAX, i64::min)
 }
 
 fn map_range(range: Range<i64>, map: &Vec<(Range<i64>, i64, i64)>) -> Vec<Range<i64>> {
@@ -81,7 +81,10 @@ fn map_range(range: Range<i64>, map: &Vec<(Range<i64>, i64, i64)>) -> Vec<Range<
 }
 
 #[aoc(day5, part2)]
-fn part2((seeds, maps): &MapStruct) -> i64 {
+fn part2((seeds, maps): &MapStruct) -> Result<i64, Box<dyn Error>> {
+    if seeds.len() % 2 != 0 {
+        return Err("Seeds don't come in pairs".into());
+    }
     let mut seed_ranges = seeds
         .chunks(2)
         .map(|seed| seed[0]..(seed[0] + seed[1]))
@@ -93,15 +96,13 @@ fn part2((seeds, maps): &MapStruct) -> i64 {
             .collect::<Vec<_>>();
     }
 
-    seed_ranges
+    Ok(seed_ranges
         .iter()
-        .fold(i64::MAX, |acc, seed| acc.min(seed.start))
+        .fold(i64::MAX, |acc, seed| acc.min(seed.start)))
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     const TESTCASE: &str = r#"seeds: 79 14 55 13
 
 seed-to-soil map:
@@ -136,13 +137,7 @@ humidity-to-location map:
 60 56 37
 56 93 4"#;
 
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 35);
-    }
-
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse(TESTCASE)), 46);
+    examples! {
+        TESTCASE => part1: 35, part2: 46;
     }
 }
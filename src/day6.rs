@@ -1,41 +1,61 @@
+use std::error::Error;
+
 use aoc_runner_derive::{aoc, aoc_generator};
 use regex::Regex;
 
 type ParsedInput = Vec<(i64, i64)>;
 
 #[aoc_generator(day6, part1)]
-fn parse(input: &str) -> ParsedInput {
+fn parse(input: &str) -> Result<ParsedInput, Box<dyn Error>> {
     let mut l = input.lines();
 
     let re = Regex::new(r"([0-9])+").unwrap();
     let times = re
-        .find_iter(l.next().unwrap())
-        .map(|m| m.as_str().parse::<i64>().unwrap());
+        .find_iter(l.next().ok_or("Missing times")?)
+        .map(|m| m.as_str().parse::<i64>())
+        .collect::<Result<Vec<_>, _>>()?;
     let distances = re
-        .find_iter(l.next().unwrap())
-        .map(|m| m.as_str().parse::<i64>().unwrap());
+        .find_iter(l.next().ok_or("Missing distances")?)
+        .map(|m| m.as_str().parse::<i64>())
+        .collect::<Result<Vec<_>, _>>()?;
 
-    times.zip(distances).collect()
+    Ok(times.into_iter().zip(distances).collect())
 }
 
-#[aoc(day6, part1)]
-fn part1(input: &ParsedInput) -> i64 {
-    let mut res = 1;
-
-    for (time, distance) in input {
-        let mut variants = 0;
-        for i in 0..*time {
-            if i * (time - i) > *distance {
-                variants += 1;
-            }
+/// Number of ways to hold the button that beat the record.
+/// The distance only grows until half of the time, so the shortest winning hold is found by
+/// binary search and the longest one is symmetric to it.
+fn ways_to_win(time: i64, distance: i64) -> i64 {
+    let beats = |i: i64| i128::from(i) * i128::from(time - i) > i128::from(distance);
+    let (mut lo, mut hi) = (0, time / 2 + 1);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if beats(mid) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
         }
+    }
+
+    if lo > time / 2 {
+        0
+    } else {
+        time - 2 * lo + 1
+    }
+}
 
+#[aoc(day6, part1)]
+fn part1(input: &ParsedInput) -> Result<i64, Box<dyn Error>> {
+    let mut res = 1i64;
+
+    for &(time, distance) in input {
+        let variants = ways_to_win(time, distance);
         if variants > 0 {
-            res *= variants;
+            res = res.checked_mul(variants).ok_or("The product overflows")?;
         }
     }
 
-    res
+    Ok(res)
 }
 
 // TODO: finish
@@ -66,37 +86,28 @@ fn part1(input: &ParsedInput) -> i64 {
 // }
 
 #[aoc_generator(day6, part2)]
-fn parse_2(input: &str) -> (i64, i64) {
+fn parse_2(input: &str) -> Result<(i64, i64), Box<dyn Error>> {
     let mut l = input.lines();
 
     let re = Regex::new(r"([0-9])+").unwrap();
     // Feels a bit too complex
     let times = re
-        .find_iter(l.next().unwrap())
+        .find_iter(l.next().ok_or("Missing time")?)
         .flat_map(|m| m.as_str().chars())
         .collect::<String>()
-        .parse::<i64>()
-        .unwrap();
+        .parse::<i64>()?;
     let distances = re
-        .find_iter(l.next().unwrap())
+        .find_iter(l.next().ok_or("Missing distance")?)
         .flat_map(|m| m.as_str().chars())
         .collect::<String>()
-        .parse::<i64>()
-        .unwrap();
+        .parse::<i64>()?;
 
-    (times, distances)
+    Ok((times, distances))
 }
 
 #[aoc(day6, part2)]
-fn part2((time, distance): &(i64, i64)) -> i64 {
-    let mut variants = 0;
-    for i in 0..*time {
-        if i * (time - i) > *distance {
-            variants += 1;
-        }
-    }
-
-    variants
+fn part2(&(time, distance): &(i64, i64)) -> i64 {
+    ways_to_win(time, distance)
 }
 
 #[aoc(day6, part2, quadratic)]
@@ -115,27 +126,24 @@ fn part2_quadratic((time, distance): &(i64, i64)) -> i64 {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     const TESTCASE: &str = r#"Time:      7  15   30
     Distance:  9  40  200"#;
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 288);
-    }
 
-    // #[test]
-    // fn part1_quadratic_example() {
-    //     assert_eq!(part1_quadratic(&parse(TESTCASE)).unwrap(), 288);
-    // }
-
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse_2(TESTCASE)), 71503);
+    examples! {
+        TESTCASE => part1: 288, part2: 71503;
     }
 
     #[test]
-    fn part2_quadratic_example() {
-        assert_eq!(part2_quadratic(&parse_2(TESTCASE)), 71503);
+    fn ways_to_win_counts_every_hold() {
+        for time in 0..40 {
+            for distance in 0..420 {
+                let brute_force = (0..time).filter(|i| i * (time - i) > distance).count();
+                assert_eq!(
+                    super::ways_to_win(time, distance),
+                    brute_force as i64,
+                    "{time} {distance}"
+                );
+            }
+        }
     }
 }
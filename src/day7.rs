@@ -1,5 +1,6 @@
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::error::Error;
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
@@ -22,8 +23,8 @@ enum Card {
 }
 
 impl Card {
-    fn new(card_char: char) -> Self {
-        match card_char.to_ascii_lowercase() {
+    fn new(card_char: char) -> Result<Self, String> {
+        Ok(match card_char.to_ascii_lowercase() {
             '2' => Self::Two,
             '3' => Self::Three,
             '4' => Self::Four,
@@ -37,8 +38,8 @@ impl Card {
             'q' => Self::Queen,
             'k' => Self::King,
             'a' => Self::Ace,
-            _ => unreachable!("Invalid card!"),
-        }
+            _ => return Err(format!("Invalid card: {card_char}")),
+        })
     }
 }
 
@@ -57,7 +58,7 @@ enum Hand {
 struct Game {
     hand: Hand,
     cards: Vec<Card>,
-    bet: i64,
+    bet: u32,
 }
 
 impl PartialOrd for Game {
@@ -77,7 +78,7 @@ impl Ord for Game {
                     Ordering::Equal => None,
                     o => Some(o),
                 })
-                .unwrap(),
+                .unwrap_or(Ordering::Equal),
             o => o,
         }
     }
@@ -85,12 +86,20 @@ impl Ord for Game {
 
 type ParsedInput = Vec<Game>;
 #[aoc_generator(day7, part1)]
-fn parse(input: &str) -> ParsedInput {
+fn parse(input: &str) -> Result<ParsedInput, Box<dyn Error>> {
     input
         .lines()
-        .map(|l| {
-            let mut l = l.split_ascii_whitespace();
-            let cards = l.next().unwrap().chars().map(Card::new).collect::<Vec<_>>();
+        .map(|line| {
+            let mut l = line.split_ascii_whitespace();
+            let cards = l
+                .next()
+                .ok_or("Empty line")?
+                .chars()
+                .map(Card::new)
+                .collect::<Result<Vec<_>, _>>()?;
+            if cards.len() != 5 {
+                return Err(format!("A hand needs 5 cards: {line}").into());
+            }
 
             let mut card_counts = HashMap::new();
             for c in &cards {
@@ -120,13 +129,13 @@ fn parse(input: &str) -> ParsedInput {
                 _ => unreachable!(),
             };
 
-            Game {
+            Ok(Game {
                 hand,
                 cards,
-                bet: l.next().unwrap().parse::<i64>().unwrap(),
-            }
+                bet: l.next().ok_or("Missing bet")?.parse::<u32>()?,
+            })
         })
-        .collect::<Vec<_>>()
+        .collect()
 }
 
 #[aoc(day7, part1)]
@@ -141,8 +150,8 @@ fn part1(input: &ParsedInput) -> i64 {
 }
 
 #[aoc_generator(day7, part2)]
-fn parse2(input: &str) -> ParsedInput {
-    let mut out = parse(input);
+fn parse2(input: &str) -> Result<ParsedInput, Box<dyn Error>> {
+    let mut out = parse(input)?;
     for g in &mut out {
         let mut jokers = 0;
         g.cards.iter_mut().for_each(|c| {
@@ -188,7 +197,7 @@ fn parse2(input: &str) -> ParsedInput {
         }
     }
 
-    out
+    Ok(out)
 }
 
 #[aoc(day7, part2)]
@@ -204,8 +213,6 @@ fn part2(input: &ParsedInput) -> i64 {
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     const TESTCASE_R: &str = r#"2345A 1
 Q2KJJ 13
 Q2Q2Q 19
@@ -225,28 +232,14 @@ AAAAA 61
 2AAAA 23
 2JJJJ 53
 JJJJ2 41"#;
-    #[test]
-    fn part1_example_r() {
-        assert_eq!(part1(&parse(TESTCASE_R)), 6592);
-    }
-
-    #[test]
-    fn part2_example_r() {
-        assert_eq!(part2(&parse2(TESTCASE_R)), 6839);
-    }
-
     const TESTCASE: &str = r#"32T3K 765
 T55J5 684
 KK677 28
 KTJJT 220
 QQQJA 483"#;
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 6440);
-    }
 
-    // #[test]
-    // fn part2_example() {
-    //     assert_eq!(part2(&parse2(TESTCASE)).unwrap(), 5905);
-    // }
+    examples! {
+        TESTCASE_R => part1: 6592, part2: 6839;
+        TESTCASE => part1: 6440, part2: 5905;
+    }
 }
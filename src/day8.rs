@@ -1,96 +1,285 @@
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+};
 
-use aoc_runner_derive::{aoc, aoc_generator};
 use num_integer::Integer;
 use regex::Regex;
 
+use crate::{assumptions::Assumption, dot::Graph};
+
+/// The network with its node names borrowed from the input
 #[derive(Debug)]
-struct Network {
-    instructions: Vec<char>,
-    nodes: HashMap<String, (String, String)>,
+pub(crate) struct Network<'a> {
+    /// `L` and `R` bytes
+    instructions: &'a [u8],
+    nodes: HashMap<&'a str, (&'a str, &'a str)>,
 }
 
-type ParsedInput = Network;
-
-#[aoc_generator(day8)]
-fn parse(input: &str) -> ParsedInput {
+pub(crate) fn parse(input: &str) -> Result<Network<'_>, Box<dyn Error>> {
     let mut l = input.lines();
-    let instructions = l.next().unwrap().chars().collect::<Vec<_>>();
+    let instructions = l.next().ok_or("Missing instructions")?;
+    if instructions.is_empty() {
+        return Err("Missing instructions".into());
+    }
+    if let Some(c) = instructions.chars().find(|c| !matches!(c, 'L' | 'R')) {
+        return Err(format!("Unknown instruction {c}").into());
+    }
 
     let re = Regex::new(r"([a-zA-Z0-9]+)").unwrap();
     let nodes = l
         .skip(1)
-        .map(|l| {
-            let mut i = re.find_iter(l);
-            let start = i.next().unwrap().as_str().to_string();
-            let l = i.next().unwrap().as_str().to_string();
-            let r = i.next().unwrap().as_str().to_string();
-            (start, (l, r))
+        .map(|line| {
+            let mut i = re.find_iter(line).map(|m| m.as_str());
+            match (i.next(), i.next(), i.next()) {
+                (Some(start), Some(l), Some(r)) => Ok((start, (l, r))),
+                _ => Err(format!("Invalid node: {line}")),
+            }
         })
-        .collect::<HashMap<_, _>>();
+        .collect::<Result<HashMap<_, _>, _>>()?;
 
-    Network {
-        instructions,
+    Ok(Network {
+        instructions: instructions.as_bytes(),
         nodes,
-    }
+    })
 }
 
-#[aoc(day8, part1)]
-fn part1(input: &ParsedInput) -> i64 {
-    let mut cur = "AAA".to_string();
-    let mut counter = 0;
-    let mut i = input.instructions.iter().cycle();
-    while cur != "ZZZ" {
-        let cur_map = input.nodes.get(&cur).expect("Unknown node.");
-        match i.next().unwrap() {
-            'L' => cur = cur_map.0.clone(),
-            'R' => cur = cur_map.1.clone(),
-            _ => unreachable!(),
+impl<'a> Network<'a> {
+    /// Steps from `start` to the first node satisfying `end`. After more steps than there are
+    /// (node, instruction) states the walk is going in circles and never gets there.
+    fn steps(&self, start: &str, end: impl Fn(&str) -> bool) -> Result<i64, String> {
+        let limit = self.nodes.len() * self.instructions.len();
+        let mut cur = start;
+        let mut i = self.instructions.iter().cycle();
+        for counter in 0..=limit {
+            if end(cur) {
+                debug!(start, end = cur, steps = counter, "reached an end node");
+                return Ok(counter as i64);
+            }
+            let cur_map = self
+                .nodes
+                .get(cur)
+                .ok_or_else(|| format!("Unknown node {cur}"))?;
+            cur = match i.next() {
+                Some(b'L') => cur_map.0,
+                _ => cur_map.1,
+            };
         }
 
-        counter += 1;
+        warn!(start, limit, "walking in circles");
+        Err(format!("{start} never reaches an end node"))
+    }
+
+    /// The (node, instruction) moves of [`Network::steps`], for drawing them
+    fn path(
+        &self,
+        start: &'a str,
+        end: impl Fn(&str) -> bool,
+    ) -> Result<Vec<(&'a str, u8)>, String> {
+        let limit = self.nodes.len() * self.instructions.len();
+        let mut cur = start;
+        let mut path = vec![];
+        let mut i = self.instructions.iter().cycle();
+        for _ in 0..=limit {
+            if end(cur) {
+                return Ok(path);
+            }
+            let cur_map = self
+                .nodes
+                .get(cur)
+                .ok_or_else(|| format!("Unknown node {cur}"))?;
+            let dir = *i.next().unwrap();
+            path.push((cur, dir));
+            cur = match dir {
+                b'L' => cur_map.0,
+                _ => cur_map.1,
+            };
+        }
+
+        Err(format!("{start} never reaches an end node"))
+    }
+
+    /// The nodes a ghost starting at `start` ends up walking around forever
+    fn ghost_cycle(&self, start: &'a str) -> HashSet<&'a str> {
+        let len = self.instructions.len();
+        let mut seen = HashMap::new();
+        let mut walk = vec![];
+        let mut cur = start;
+        let mut step = 0;
+        while !seen.contains_key(&(cur, step % len)) {
+            seen.insert((cur, step % len), step);
+            walk.push(cur);
+            let Some(&(l, r)) = self.nodes.get(cur) else {
+                return HashSet::new();
+            };
+            cur = match self.instructions[step % len] {
+                b'L' => l,
+                _ => r,
+            };
+            step += 1;
+        }
+        walk[seen[&(cur, step % len)]..].iter().copied().collect()
     }
 
-    counter
+    /// Every node with its left and right edges. The path from `AAA` to `ZZZ` is drawn in red,
+    /// and the nodes each ghost (starting on a node ending in `A`) keeps cycling through are
+    /// filled with the ghost's color.
+    fn to_dot(&self) -> String {
+        const COLORS: [&str; 6] = [
+            "lightblue",
+            "palegreen",
+            "khaki",
+            "plum",
+            "lightsalmon",
+            "lightgray",
+        ];
+
+        let mut names = self.nodes.keys().copied().collect::<Vec<_>>();
+        names.sort_unstable();
+        let path = if self.nodes.contains_key("AAA") {
+            self.path("AAA", |n| n == "ZZZ").unwrap_or_default()
+        } else {
+            vec![]
+        };
+        let on_path = |n: &str| (n == "ZZZ" && !path.is_empty()) || path.iter().any(|p| p.0 == n);
+        let mut cycles = HashMap::new();
+        for (i, start) in names.iter().filter(|n| n.ends_with('A')).enumerate() {
+            for n in self.ghost_cycle(start) {
+                cycles.entry(n).or_insert(COLORS[i % COLORS.len()]);
+            }
+        }
+
+        let mut g = Graph::digraph("day8");
+        g.attr("rankdir", "LR");
+        for &n in &names {
+            let mut attrs = vec![];
+            if n.ends_with('A') {
+                attrs.push(("shape", "box"));
+            } else if n.ends_with('Z') {
+                attrs.push(("shape", "doublecircle"));
+            }
+            if let Some(color) = cycles.get(n) {
+                attrs.extend([("style", "filled"), ("fillcolor", *color)]);
+            }
+            if on_path(n) {
+                attrs.extend([("color", "red"), ("penwidth", "2")]);
+            }
+            g.node(n, &attrs);
+        }
+        for &n in &names {
+            let (l, r) = self.nodes[n];
+            let used = |dir| path.contains(&(n, dir));
+            let edges = if l == r {
+                vec![(l, "L/R", used(b'L') || used(b'R'))]
+            } else {
+                vec![(l, "L", used(b'L')), (r, "R", used(b'R'))]
+            };
+            for (to, label, used) in edges {
+                let mut attrs = vec![("label", label)];
+                if used {
+                    attrs.extend([("color", "red"), ("penwidth", "2")]);
+                }
+                g.edge(n, to, &attrs);
+            }
+        }
+        g.render()
+    }
+}
+
+/// The network as a Graphviz graph, see [`Network::to_dot`]
+pub(crate) fn dot(input: &str) -> Result<String, Box<dyn Error>> {
+    Ok(parse(input)?.to_dot())
+}
+
+pub(crate) fn part1(input: &Network) -> Result<i64, Box<dyn Error>> {
+    Ok(input.steps("AAA", |n| n == "ZZZ")?)
 }
 
-#[aoc(day8, part2)]
 // This one is a minor bruh moment
 // I've kind of cheated here, because I've seen that someone else is using LCM to solve it.
 // The inputs have a propery not mentioned in the description.
 // Each start node maps to a distinct end node, and the end node maps to itself.
-fn part2(input: &ParsedInput) -> i64 {
+pub(crate) fn part2(input: &Network) -> Result<i64, Box<dyn Error>> {
     let starts = input
         .nodes
-        .iter()
-        .filter_map(|n| if n.0.ends_with('A') { Some(n.0) } else { None })
+        .keys()
+        .copied()
+        .filter(|n| n.ends_with('A'))
         .collect::<Vec<_>>();
 
     let mut counters = vec![];
+    for s in starts {
+        counters.push(input.steps(s, |n| n.ends_with('Z'))?);
+    }
+
+    counters
+        .iter()
+        .copied()
+        .reduce(|a, b| a.lcm(&b))
+        .ok_or_else(|| "No start nodes".into())
+}
+
+pub(crate) static ASSUMPTIONS: &[Assumption] = &[Assumption {
+    part: 2,
+    description: "Every ghost reaches end nodes at exact multiples of its first end node",
+    check: ghosts_cycle_cleanly,
+}];
+
+/// The LCM in part 2 is only the answer if each ghost, after reaching its first end node at step
+/// `n`, keeps reaching end nodes at exactly `2n`, `3n` and so on
+fn ghosts_cycle_cleanly(input: &str) -> Result<(), String> {
+    let input = parse(input).map_err(|e| e.to_string())?;
+    let len = input.instructions.len();
+    // Walking longer than there are (node, instruction) states without an end node means looping
+    let limit = input.nodes.len() * len;
+
+    let mut starts = input
+        .nodes
+        .keys()
+        .copied()
+        .filter(|n| n.ends_with('A'))
+        .collect::<Vec<_>>();
+    starts.sort();
     for s in starts {
         let mut cur = s;
-        let mut counter = 0;
-        let mut i = input.instructions.iter().cycle();
-        while !cur.ends_with('Z') {
-            let cur_map = input.nodes.get(cur).expect("Unknown node.");
-            match i.next().unwrap() {
-                'L' => cur = &cur_map.0,
-                'R' => cur = &cur_map.1,
-                _ => unreachable!(),
-            }
+        let mut first = None;
+        let mut seen = HashSet::new();
+        let mut since_end = 0;
+        for step in 1.. {
+            let cur_map = input.nodes.get(cur).ok_or(format!("Unknown node {cur}"))?;
+            cur = match input.instructions[(step - 1) % len] {
+                b'L' => cur_map.0,
+                b'R' => cur_map.1,
+                c => return Err(format!("Unknown instruction {}", c as char)),
+            };
+            since_end += 1;
 
-            counter += 1;
+            if cur.ends_with('Z') {
+                let first = *first.get_or_insert(step);
+                if step % first != 0 {
+                    return Err(format!(
+                        "{s} reaches an end node after {step} steps, which is not a multiple of {first}"
+                    ));
+                }
+                // Back in a state we've been in, so everything from here on repeats
+                if !seen.insert((cur, step % len)) {
+                    break;
+                }
+                since_end = 0;
+            } else if since_end > limit {
+                return Err(match first {
+                    None => format!("{s} never reaches an end node"),
+                    Some(_) => format!("{s} never returns to an end node"),
+                });
+            }
         }
-        counters.push(counter);
     }
 
-    counters.iter().copied().reduce(|a, b| a.lcm(&b)).unwrap()
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     const TESTCASE: &str = r#"RL
 
 AAA = (BBB, CCC)
@@ -105,14 +294,6 @@ ZZZ = (ZZZ, ZZZ)"#;
 AAA = (BBB, BBB)
 BBB = (AAA, ZZZ)
 ZZZ = (ZZZ, ZZZ)"#;
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 2);
-    }
-    #[test]
-    fn part1_example2() {
-        assert_eq!(part1(&parse(TESTCASE2)), 6);
-    }
 
     const TESTCASE_P2: &str = r#"LR
 
@@ -124,8 +305,16 @@ ZZZ = (ZZZ, ZZZ)"#;
 22C = (22Z, 22Z)
 22Z = (22B, 22B)
 XXX = (XXX, XXX)"#;
+
+    examples! {
+        TESTCASE => part1: 2;
+        TESTCASE2 => part1: 6;
+        TESTCASE_P2 => part2: 6;
+    }
+
     #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse(TESTCASE_P2)), 6);
+    fn dot() {
+        crate::dot::assert_golden("day8.dot", &super::dot(TESTCASE2).unwrap());
+        crate::dot::assert_golden("day8_ghosts.dot", &super::dot(TESTCASE_P2).unwrap());
     }
 }
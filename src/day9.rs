@@ -1,35 +1,48 @@
+use std::error::Error;
+
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use crate::input::Record;
+
 type ParsedInput = Vec<Vec<i64>>;
 
 #[aoc_generator(day9)]
-fn parse(input: &str) -> ParsedInput {
-    input
-        .lines()
-        .map(|l| {
-            l.split_ascii_whitespace()
-                .map(|n| n.parse::<i64>().expect("Could not parse a number"))
-                .collect::<Vec<_>>()
+fn parse(input: &str) -> Result<ParsedInput, Box<dyn Error>> {
+    input.lines().map(parse_history).collect()
+}
+
+fn parse_history(line: &str) -> Result<Vec<i64>, Box<dyn Error>> {
+    line.split_ascii_whitespace()
+        .map(|n| {
+            n.parse::<i64>()
+                .map_err(|e| format!("Could not parse {n}: {e}").into())
         })
-        .collect::<Vec<_>>()
+        .collect()
 }
 
-fn extrapolate(history: &[i64]) -> i64 {
+/// `None` if a difference or the prediction overflows
+fn extrapolate(history: &[i64]) -> Option<i64> {
     if history.iter().all(|n| *n == 0) {
-        0
+        Some(0)
     } else {
-        let diffs = history.windows(2).map(|w| w[1] - w[0]).collect::<Vec<_>>();
-        history.last().unwrap() + extrapolate(&diffs)
+        let diffs = history
+            .windows(2)
+            .map(|w| w[1].checked_sub(w[0]))
+            .collect::<Option<Vec<_>>>()?;
+        history.last()?.checked_add(extrapolate(&diffs)?)
     }
 }
 
 #[aoc(day9, part1)]
-fn part1(input: &ParsedInput) -> i64 {
-    input.iter().map(|h| extrapolate(h)).sum()
+fn part1(input: &ParsedInput) -> Result<i64, Box<dyn Error>> {
+    input
+        .iter()
+        .try_fold(0i64, |acc, h| acc.checked_add(extrapolate(h)?))
+        .ok_or_else(|| "The prediction overflows".into())
 }
 
 #[aoc(day9, part2)]
-fn part2(input: &ParsedInput) -> i64 {
+fn part2(input: &ParsedInput) -> Result<i64, Box<dyn Error>> {
     part1(
         &input
             .iter()
@@ -38,20 +51,39 @@ fn part2(input: &ParsedInput) -> i64 {
     )
 }
 
+pub(crate) fn part1_lines<'a>(
+    lines: impl Iterator<Item = Record<'a>>,
+) -> Result<i64, Box<dyn Error>> {
+    sum_predictions(lines.map(|l| parse_history(&l?)))
+}
+
+pub(crate) fn part2_lines<'a>(
+    lines: impl Iterator<Item = Record<'a>>,
+) -> Result<i64, Box<dyn Error>> {
+    sum_predictions(lines.map(|l| {
+        let mut history = parse_history(&l?)?;
+        history.reverse();
+        Ok(history)
+    }))
+}
+
+fn sum_predictions(
+    mut histories: impl Iterator<Item = Result<Vec<i64>, Box<dyn Error>>>,
+) -> Result<i64, Box<dyn Error>> {
+    histories.try_fold(0i64, |acc, h| {
+        Ok(extrapolate(&h?)
+            .and_then(|p| acc.checked_add(p))
+            .ok_or("The prediction overflows")?)
+    })
+}
+
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     const TESTCASE: &str = r#"0 3 6 9 12 15
 1 3 6 10 15 21
 10 13 16 21 30 45"#;
-    #[test]
-    fn part1_example() {
-        assert_eq!(part1(&parse(TESTCASE)), 114);
-    }
 
-    #[test]
-    fn part2_example() {
-        assert_eq!(part2(&parse(TESTCASE)), 2);
+    examples! {
+        TESTCASE => part1: 114, part2: 2;
     }
 }
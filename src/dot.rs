@@ -0,0 +1,140 @@
+//! Graphviz DOT output of the puzzle graphs, to look at them with e.g.
+//! `dot -Tsvg day8.dot -o day8.svg`.
+//!
+//! Days 8 (the network), 10 (the pipe loop) and 17 (the crucible states around the start) can be
+//! exported, see [`export`].
+
+use std::{error::Error, fmt::Write};
+
+use crate::{day10, day17, day8};
+
+/// Days that can be exported
+pub const DAYS: [u32; 3] = [8, 10, 17];
+
+/// A graph written out in insertion order, so the output is stable
+pub struct Graph {
+    name: String,
+    directed: bool,
+    lines: Vec<String>,
+}
+
+impl Graph {
+    pub fn digraph(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            directed: true,
+            lines: vec![],
+        }
+    }
+
+    pub fn undirected(name: &str) -> Self {
+        Self {
+            directed: false,
+            ..Self::digraph(name)
+        }
+    }
+
+    /// Attribute of the whole graph
+    pub fn attr(&mut self, key: &str, value: &str) -> &mut Self {
+        self.lines.push(format!("{key}={}", quote(value)));
+        self
+    }
+
+    pub fn node(&mut self, id: &str, attrs: &[(&str, &str)]) -> &mut Self {
+        self.lines
+            .push(format!("{}{}", quote(id), attr_list(attrs)));
+        self
+    }
+
+    pub fn edge(&mut self, from: &str, to: &str, attrs: &[(&str, &str)]) -> &mut Self {
+        let arrow = if self.directed { "->" } else { "--" };
+        self.lines.push(format!(
+            "{} {arrow} {}{}",
+            quote(from),
+            quote(to),
+            attr_list(attrs)
+        ));
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let kind = if self.directed { "digraph" } else { "graph" };
+        let mut out = format!("{kind} {} {{\n", quote(&self.name));
+        for l in &self.lines {
+            writeln!(out, "    {l};").unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+fn attr_list(attrs: &[(&str, &str)]) -> String {
+    if attrs.is_empty() {
+        return String::new();
+    }
+    let attrs = attrs
+        .iter()
+        .map(|(k, v)| format!("{k}={}", quote(v)))
+        .collect::<Vec<_>>();
+    format!(" [{}]", attrs.join(", "))
+}
+
+/// The graph of a day's input. `radius` bounds the day 17 states to the ones at most that many
+/// tiles from the start.
+pub fn export(day: u32, input: &str, radius: usize) -> Result<String, Box<dyn Error>> {
+    match day {
+        8 => day8::dot(input),
+        10 => day10::dot(input),
+        17 => day17::dot(input, radius),
+        _ => Err(format!("Day {day} has no graph to export, only days {DAYS:?} do").into()),
+    }
+}
+
+/// Compares `actual` with `tests/golden/<name>`. With `UPDATE_GOLDEN` set the file is rewritten
+/// instead.
+#[cfg(test)]
+pub(crate) fn assert_golden(name: &str, actual: &str) {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "{}: {e}, run with UPDATE_GOLDEN=1 to create it",
+            path.display()
+        )
+    });
+    assert!(
+        actual == expected,
+        "{name} differs from the golden file, run with UPDATE_GOLDEN=1 to update it\n{actual}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_and_attributes() {
+        let mut g = Graph::undirected("a \"b\"");
+        g.attr("layout", "neato")
+            .node("x", &[("label", "1\n2")])
+            .edge("x", "y\\", &[]);
+        assert_eq!(
+            g.render(),
+            "graph \"a \\\"b\\\"\" {\n    layout=\"neato\";\n    \"x\" [label=\"1\\n2\"];\n    \"x\" -- \"y\\\\\";\n}\n"
+        );
+        assert!(export(1, "", 0).is_err());
+    }
+}
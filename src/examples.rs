@@ -0,0 +1,121 @@
+//! Example tests against every registered variant.
+//!
+//! ```text
+//! examples! {
+//!     TESTCASE => part1: 6440, part2: 5905;
+//!     TESTCASE_R => part2: 6839;
+//! }
+//! ```
+//!
+//! expands to a single `examples` test running each input through every variant of the listed
+//! parts, generator included, so part-specific generators are covered as well. Parts with a
+//! streaming solver (see [`crate::input`]) are checked with it too. It also adds a
+//! `fuzz` test using the inputs as seeds (see [`crate::fuzz`]).
+
+use std::io::Cursor;
+
+use crate::{
+    input::{self, Input},
+    registry,
+};
+
+/// Declares the examples of the day whose module it is invoked in.
+///
+/// All parts and variants are checked in one `examples` test rather than a test each. It collects
+/// every mismatch before failing, so a single run lists all the variants that are wrong.
+#[cfg(test)]
+macro_rules! examples {
+    ($($input:expr => $($part:ident: $expected:expr),+ $(,)?);+ $(;)?) => {
+        #[test]
+        fn examples() {
+            let mut failures = vec![];
+            $($(
+                failures.extend($crate::examples::check(
+                    module_path!(),
+                    stringify!($part),
+                    $input,
+                    $expected,
+                ));
+            )+)+
+            assert!(failures.is_empty(), "{}", failures.join("\n"));
+        }
+
+        #[test]
+        fn fuzz() {
+            $crate::fuzz::check(module_path!(), &[$($input),+]);
+        }
+    };
+}
+
+/// Checks an example against every variant of the part.
+/// `module` is the `module_path!()` of the day (or anything under it) and `part` is `part1`/`part2`.
+#[cfg(test)]
+pub(crate) fn check(
+    module: &str,
+    part: &str,
+    input: &str,
+    expected: impl std::fmt::Display,
+) -> Vec<String> {
+    let day = day_of(module);
+    let part = part
+        .strip_prefix("part")
+        .and_then(|p| p.parse::<u32>().ok())
+        .unwrap_or_else(|| panic!("Invalid part: {part}"));
+
+    let mut failures = vec![];
+    if registry::variants(day, part).next().is_none() {
+        failures.push(format!("Day {day} - Part {part} is not registered"));
+    }
+    failures.extend(verify(day, part, input, &expected.to_string()));
+    failures
+}
+
+/// The day of a `module_path!()` inside a day module
+#[cfg(test)]
+pub(crate) fn day_of(module: &str) -> u32 {
+    module
+        .split("::")
+        .find_map(|m| m.strip_prefix("day")?.parse::<u32>().ok())
+        .unwrap_or_else(|| panic!("{module} is not a day module"))
+}
+
+/// Runs `input` through every registered variant of the part and its streaming solver, returning
+/// each mismatch
+pub(crate) fn verify(day: u32, part: u32, input: &str, expected: &str) -> Vec<String> {
+    let mut failures = vec![];
+    for s in registry::variants(day, part) {
+        match s.solve(input) {
+            Ok(a) if a.answer == expected => (),
+            Ok(a) => failures.push(format!("{s}: expected {expected}, got {}", a.answer)),
+            Err(e) => failures.push(format!("{s}: {e}")),
+        }
+    }
+    if let Some(s) = input::find(day, part) {
+        let name = format!("Day {day} - Part {part} - streaming");
+        match (s.solve)(&mut Input::from_reader(Cursor::new(input.to_owned()))) {
+            Ok(a) if a == expected => (),
+            Ok(a) => failures.push(format!("{name}: expected {expected}, got {a}")),
+            Err(e) => failures.push(format!("{name}: {e}")),
+        }
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_reports_mismatches() {
+        // Day 0 is a test fixture whose `off_by_one` variant is wrong on purpose
+        assert!(check("advent_of_code_2023::day0::tests", "part1", "...", 0).is_empty());
+        assert_eq!(
+            check("advent_of_code_2023::day0::tests", "part1", ".#.", 1),
+            ["Day 0 - Part 1 - off_by_one: expected 1, got 2"]
+        );
+        assert_eq!(
+            check("advent_of_code_2023::day25", "part1", ".#.", 1),
+            ["Day 25 - Part 1 is not registered"]
+        );
+    }
+}
@@ -0,0 +1,206 @@
+//! Example fixtures extracted from saved puzzle pages.
+//!
+//! `fixtures/dayN/partP.txt` holds the example input and `fixtures/dayN/partP.answer` the expected
+//! answer. Every fixture is checked against every variant of its part by the test suite.
+
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+
+use crate::{examples, registry};
+
+/// Default fixture directory, relative to the crate root
+pub const FIXTURE_DIR: &str = "fixtures";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    pub day: u32,
+    pub part: u32,
+    pub input: String,
+    pub answer: String,
+}
+
+/// Pulls the example inputs and answers out of a saved puzzle page.
+///
+/// Each `<article>` is one part. The example answer is the last emphasized code in the article
+/// (`<code><em>142</em></code>`), and its input is the last `<pre><code>` block before it.
+/// Part 2 usually has no example block of its own and reuses the one from part 1.
+pub fn extract(html: &str) -> Result<Vec<Fixture>, Box<dyn Error>> {
+    let title = Regex::new(r"<h2>--- Day (\d+):").unwrap();
+    let day = title.captures(html).ok_or("Could not find the day title")?[1].parse::<u32>()?;
+
+    let article = Regex::new(r"(?s)<article[^>]*>(.*?)</article>").unwrap();
+    let block = Regex::new(r"(?s)<pre><code>(.*?)</code></pre>").unwrap();
+    let answer =
+        Regex::new(r"<code><em>([^<]+)</em></code>|<em><code>([^<]+)</code></em>").unwrap();
+
+    let mut fixtures = vec![];
+    let mut last_block = None;
+    for (i, a) in article.captures_iter(html).enumerate() {
+        let a = a.get(1).unwrap().as_str();
+        let Some(ans) = answer.captures_iter(a).last() else {
+            continue;
+        };
+        let ans_pos = ans.get(0).unwrap().start();
+
+        if let Some(b) = block
+            .captures_iter(&a[..ans_pos])
+            .last()
+            .map(|b| b.get(1).unwrap().as_str())
+        {
+            last_block = Some(decode(b));
+        }
+        let Some(input) = &last_block else {
+            continue;
+        };
+
+        fixtures.push(Fixture {
+            day,
+            part: i as u32 + 1,
+            input: input.trim_end_matches('\n').to_owned(),
+            answer: decode(ans.get(1).or(ans.get(2)).unwrap().as_str()),
+        });
+    }
+
+    Ok(fixtures)
+}
+
+/// Strips tags (e.g. emphasis inside examples) and decodes HTML entities
+fn decode(s: &str) -> String {
+    let tags = Regex::new(r"<[^>]*>").unwrap();
+    tags.replace_all(s, "")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+fn fixture_paths(dir: &Path, day: u32, part: u32) -> (PathBuf, PathBuf) {
+    let dir = dir.join(format!("day{day}"));
+    (
+        dir.join(format!("part{part}.txt")),
+        dir.join(format!("part{part}.answer")),
+    )
+}
+
+/// Writes the fixtures under `dir`, replacing existing ones for the same day and part
+pub fn write(dir: impl AsRef<Path>, fixtures: &[Fixture]) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let mut written = vec![];
+    for f in fixtures {
+        let (input, answer) = fixture_paths(dir.as_ref(), f.day, f.part);
+        fs::create_dir_all(input.parent().unwrap())?;
+        fs::write(&input, &f.input)?;
+        fs::write(&answer, format!("{}\n", f.answer))?;
+        written.extend([input, answer]);
+    }
+    Ok(written)
+}
+
+/// Every fixture under `dir`. A missing directory has no fixtures.
+pub fn load(dir: impl AsRef<Path>) -> Result<Vec<Fixture>, Box<dyn Error>> {
+    let mut fixtures = vec![];
+    for day in registry::days() {
+        for part in [1, 2] {
+            let (input, answer) = fixture_paths(dir.as_ref(), day, part);
+            if !input.exists() {
+                continue;
+            }
+            fixtures.push(Fixture {
+                day,
+                part,
+                input: fs::read_to_string(&input)?,
+                answer: fs::read_to_string(&answer)
+                    .map_err(|e| format!("{}: {e}", answer.display()))?
+                    .trim()
+                    .to_owned(),
+            });
+        }
+    }
+    Ok(fixtures)
+}
+
+/// Runs every variant on every fixture, returning a description of each mismatch
+pub fn check(fixtures: &[Fixture]) -> Vec<String> {
+    fixtures
+        .iter()
+        .flat_map(|f| examples::verify(f.day, f.part, &f.input, &f.answer))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::temp_dir;
+
+    const PAGE: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 9: Mirage Maintenance ---</h2>
+<p>For example:</p>
+<pre><code>0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45
+</code></pre>
+<p>Then the next value is <code><em>18</em></code>, and so on.</p>
+<p>The sum of these extrapolated values is <code><em>114</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>1234</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>Adding the new values on the left side of each history produces <em><code>2</code></em>.</p>
+</article>
+</main></body></html>"#;
+
+    #[test]
+    fn extract_from_page() {
+        let fixtures = extract(PAGE).unwrap();
+        let input = "0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45";
+        assert_eq!(
+            fixtures,
+            [
+                Fixture {
+                    day: 9,
+                    part: 1,
+                    input: input.to_owned(),
+                    answer: "114".to_owned()
+                },
+                Fixture {
+                    day: 9,
+                    part: 2,
+                    input: input.to_owned(),
+                    answer: "2".to_owned()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_entities_and_tags() {
+        assert_eq!(decode("<em>.|.</em>..\\&lt;-&gt;&amp;"), ".|...\\<->&");
+    }
+
+    #[test]
+    fn written_fixtures_are_checked() {
+        let dir = temp_dir("written_fixtures_are_checked");
+        let mut fixtures = extract(PAGE).unwrap();
+        write(&dir, &fixtures).unwrap();
+        assert_eq!(load(&dir).unwrap(), fixtures);
+        assert!(check(&fixtures).is_empty());
+
+        fixtures[1].answer = "3".to_owned();
+        // The unnamed variant and the streaming solver
+        assert_eq!(check(&fixtures).len(), 2);
+    }
+
+    #[test]
+    fn repo_fixtures() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(FIXTURE_DIR);
+        let fixtures = load(&dir).unwrap();
+        // `load` skips missing files, so a moved directory would otherwise check nothing
+        assert!(!fixtures.is_empty(), "No fixtures in {}", dir.display());
+        let failures = check(&fixtures);
+        assert!(failures.is_empty(), "{failures:#?}");
+    }
+}
@@ -0,0 +1,166 @@
+//! Fuzzing of every day's generator and solvers, seeded with the day's examples.
+//!
+//! Each iteration feeds every variant of the day either random bytes, random text made of the
+//! characters the examples use, or an example with a few mutations (characters and lines removed,
+//! duplicated, swapped or replaced). Malformed input has to come back as an error: a panic or a
+//! timeout fails the test, reporting the input (shrunk by [`minimize`] for panics).
+//!
+//! The `FUZZ_ITERATIONS` environment variable overrides the number of iterations per day.
+
+use std::{collections::BTreeSet, env, time::Duration};
+
+use crate::{
+    examples,
+    minimize::{self, Failure},
+    registry::{self, Solution},
+    sandbox::{Outcome, Sandbox},
+};
+
+const ITERATIONS: usize = 100;
+
+/// Characters that tend to break parsers, on top of the ones in the examples
+const EXTRA: &[char] = &[' ', '\n', '-', '0', '9', ':', ',', 'é'];
+
+/// xorshift64*, seeded per day so failures reproduce
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+/// One fuzz input built from the seeds
+fn generate(rng: &mut Rng, seeds: &[&str], alphabet: &[char]) -> String {
+    match rng.below(8) {
+        0 => {
+            let bytes = (0..rng.below(64))
+                .map(|_| rng.next() as u8)
+                .collect::<Vec<_>>();
+            String::from_utf8_lossy(&bytes).into_owned()
+        }
+        1 => (0..rng.below(64)).map(|_| *rng.pick(alphabet)).collect(),
+        _ => {
+            let mut input = rng.pick(seeds).to_string();
+            for _ in 0..=rng.below(3) {
+                input = mutate(rng, &input, alphabet);
+            }
+            input
+        }
+    }
+}
+
+fn mutate(rng: &mut Rng, input: &str, alphabet: &[char]) -> String {
+    let mut chars = input.chars().collect::<Vec<_>>();
+    let mut lines = input.split('\n').collect::<Vec<_>>();
+    let i = rng.below(chars.len());
+    let l = rng.below(lines.len());
+    match rng.below(7) {
+        0 if !chars.is_empty() => {
+            chars.remove(i);
+        }
+        1 => chars.insert(i, *rng.pick(alphabet)),
+        2 if !chars.is_empty() => chars[i] = *rng.pick(alphabet),
+        3 => chars.truncate(i),
+        4 => {
+            lines.remove(l);
+            return lines.join("\n");
+        }
+        5 => {
+            lines.insert(l, lines[l]);
+            return lines.join("\n");
+        }
+        _ => {
+            let m = rng.below(lines.len());
+            lines.swap(l, m);
+            return lines.join("\n");
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// Fuzzes every variant of the day whose module is `module` (a `module_path!()`), panicking with
+/// the first failing input of each variant
+pub(crate) fn check(module: &str, seeds: &[&str]) {
+    let day = examples::day_of(module);
+    let iterations = env::var("FUZZ_ITERATIONS")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(ITERATIONS);
+    let sandbox = Sandbox {
+        timeout: Duration::from_secs(10),
+        ..Default::default()
+    };
+
+    let alphabet = seeds
+        .iter()
+        .flat_map(|s| s.chars())
+        .chain(EXTRA.iter().copied())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let solutions = registry::SOLUTIONS
+        .iter()
+        .filter(|s| s.day == day)
+        .collect::<Vec<_>>();
+
+    let mut rng = Rng(0x9e37_79b9_7f4a_7c15 ^ u64::from(day));
+    let mut failures = vec![];
+    let mut failed = vec![false; solutions.len()];
+    for _ in 0..iterations {
+        let input = generate(&mut rng, seeds, &alphabet);
+        for (&s, failed) in solutions.iter().zip(&mut failed) {
+            if *failed {
+                continue;
+            }
+            if let Some(f) = run(&sandbox, s, &input) {
+                failures.push(f);
+                *failed = true;
+            }
+        }
+    }
+
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+/// Describes the failure of `solution` on `input`, if any
+fn run(sandbox: &Sandbox, solution: &'static Solution, input: &str) -> Option<String> {
+    match sandbox.run(solution, input.into()) {
+        Outcome::Solved(_) | Outcome::Failed(_) => None,
+        Outcome::Panicked(e) => {
+            let failure = Failure::Panic(solution);
+            let input = minimize::minimize(input, |s| failure.reproduces(sandbox, s))
+                .unwrap_or_else(|_| input.to_owned());
+            Some(format!("{solution} panicked on {input:?}: {e}"))
+        }
+        o @ Outcome::TimedOut(_) => Some(format!("{solution} on {input:?}: {o}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mutations_are_deterministic() {
+        let gen = |seed| {
+            let mut rng = Rng(seed);
+            (0..20)
+                .map(|_| generate(&mut rng, &["ab\ncd"], &['a', 'x', '\n']))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(gen(1), gen(1));
+        assert_ne!(gen(1), gen(2));
+    }
+}
@@ -0,0 +1,108 @@
+//! Simple polygons on the integer lattice, given as their ordered vertices.
+//!
+//! Consecutive vertices are joined by straight edges and the last one connects back to the
+//! first. Collinear vertices (e.g. every tile of a loop) are fine.
+
+/// `(x, y)`
+pub type Point = (i64, i64);
+
+/// Twice the signed area (shoelace formula), so it stays an integer. Positive when the vertices
+/// go counter-clockwise in a y-up coordinate system (clockwise on a y-down grid).
+pub fn double_signed_area(vertices: &[Point]) -> i64 {
+    edges(vertices)
+        .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
+        .sum()
+}
+
+pub fn signed_area(vertices: &[Point]) -> f64 {
+    double_signed_area(vertices) as f64 / 2.0
+}
+
+pub fn perimeter(vertices: &[Point]) -> f64 {
+    edges(vertices)
+        .map(|((x1, y1), (x2, y2))| ((x2 - x1) as f64).hypot((y2 - y1) as f64))
+        .sum()
+}
+
+/// Lattice points on the edges. The same as the perimeter if all edges are horizontal or vertical.
+pub fn boundary_points(vertices: &[Point]) -> i64 {
+    edges(vertices)
+        .map(|((x1, y1), (x2, y2))| gcd((x2 - x1).abs(), (y2 - y1).abs()))
+        .sum()
+}
+
+/// Lattice points strictly inside the polygon, by Pick's theorem: `A = I + B/2 - 1`
+pub fn interior_points(vertices: &[Point]) -> i64 {
+    (double_signed_area(vertices).abs() - boundary_points(vertices) + 2) / 2
+}
+
+/// Vertices visited by following `steps` (e.g. `(0, 3)` for 3 down) from the origin.
+/// The steps should end back at the origin.
+pub fn from_steps(steps: impl IntoIterator<Item = (i64, i64)>) -> Vec<Point> {
+    let mut pos = (0, 0);
+    let mut vertices = vec![pos];
+    for (dx, dy) in steps {
+        pos = (pos.0 + dx, pos.1 + dy);
+        vertices.push(pos);
+    }
+    // The closing edge is implicit
+    if vertices.len() > 1 && vertices.last() == vertices.first() {
+        vertices.pop();
+    }
+    vertices
+}
+
+fn edges(vertices: &[Point]) -> impl Iterator<Item = (Point, Point)> + '_ {
+    vertices
+        .iter()
+        .copied()
+        .zip(vertices.iter().copied().cycle().skip(1))
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn square() {
+        let ccw = [(0, 0), (2, 0), (2, 2), (0, 2)];
+        assert_eq!(double_signed_area(&ccw), 8);
+        assert_eq!(signed_area(&ccw), 4.0);
+        assert_eq!(perimeter(&ccw), 8.0);
+        assert_eq!(boundary_points(&ccw), 8);
+        assert_eq!(interior_points(&ccw), 1);
+
+        let mut cw = ccw;
+        cw.reverse();
+        assert_eq!(signed_area(&cw), -4.0);
+        assert_eq!(interior_points(&cw), 1);
+    }
+
+    #[test]
+    fn triangle() {
+        let t = [(0, 0), (4, 0), (0, 4)];
+        assert_eq!(signed_area(&t), 8.0);
+        assert_eq!(boundary_points(&t), 12);
+        assert_eq!(interior_points(&t), 3);
+        assert!((perimeter(&t) - (8.0 + 32f64.sqrt())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn steps() {
+        // An L: a 3x1 bar along the top and a 1x3 bar down the left side
+        let steps = [(3, 0), (0, 1), (-2, 0), (0, 2), (-1, 0), (0, -3)];
+        let vertices = from_steps(steps);
+        assert_eq!(vertices, [(0, 0), (3, 0), (3, 1), (1, 1), (1, 3), (0, 3)]);
+        assert_eq!(signed_area(&vertices), 5.0);
+        assert_eq!(boundary_points(&vertices), 12);
+        assert_eq!(interior_points(&vertices), 0);
+    }
+}
@@ -0,0 +1,295 @@
+//! Searches over implicit graphs, where the edges of a node come from a successor closure.
+//!
+//! Nodes can be anything hashable: grid positions, `(position, direction)` states, ...
+//! Grid-specific helpers take a [`Grid`] and a predicate for the cells that can be entered.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+use crate::{
+    grid::{Grid, Pos},
+    hash::{FxHashMap, FxHashSet},
+};
+
+/// Number of steps from the closest start to every reachable node
+pub fn bfs<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> FxHashMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut dist = FxHashMap::default();
+    let mut queue = VecDeque::new();
+    for s in starts {
+        if !dist.contains_key(&s) {
+            dist.insert(s.clone(), 0);
+            queue.push_back(s);
+        }
+    }
+
+    while let Some(n) = queue.pop_front() {
+        let d = dist[&n];
+        for next in successors(&n) {
+            if !dist.contains_key(&next) {
+                dist.insert(next.clone(), d + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    dist
+}
+
+/// Every node reachable from the starts, the starts included
+pub fn reachable<N, I>(
+    starts: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> FxHashSet<N>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = FxHashSet::default();
+    let mut stack = vec![];
+    for s in starts {
+        if seen.insert(s.clone()) {
+            stack.push(s);
+        }
+    }
+
+    while let Some(n) = stack.pop() {
+        for next in successors(&n) {
+            if seen.insert(next.clone()) {
+                stack.push(next);
+            }
+        }
+    }
+
+    seen
+}
+
+/// Shortest path by number of steps from `start` to the first node satisfying `success`
+pub fn bfs_path<N, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut success: impl FnMut(&N) -> bool,
+) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents = FxHashMap::default();
+    let mut queue = VecDeque::new();
+    parents.insert(start.clone(), None);
+    queue.push_back(start);
+
+    while let Some(n) = queue.pop_front() {
+        if success(&n) {
+            return Some(reconstruct(&parents, n));
+        }
+        for next in successors(&n) {
+            if !parents.contains_key(&next) {
+                parents.insert(next.clone(), Some(n.clone()));
+                queue.push_back(next);
+            }
+        }
+    }
+
+    None
+}
+
+/// Follows the parent links back from `end` and returns the path from the root to `end`
+pub fn reconstruct<N: Clone + Eq + Hash>(parents: &FxHashMap<N, Option<N>>, end: N) -> Vec<N> {
+    let mut path = vec![end];
+    while let Some(Some(p)) = parents.get(path.last().unwrap()) {
+        path.push(p.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// Cheapest path from `start` to the first node satisfying `success`, with its cost
+pub fn dijkstra<N, C, I>(
+    start: N,
+    successors: impl FnMut(&N) -> I,
+    success: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, successors, |_| C::default(), success)
+}
+
+/// Like [`dijkstra`], guided by `heuristic`. The heuristic must never overestimate the remaining
+/// cost, or the path found may not be the cheapest.
+pub fn astar<N, C, I>(
+    start: N,
+    mut successors: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut success: impl FnMut(&N) -> bool,
+) -> Option<(Vec<N>, C)>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    // Nodes are referred to by their index in `nodes`, which also holds the best known cost and
+    // the parent, so the heap doesn't need `N: Ord`
+    let mut nodes = vec![(start.clone(), C::default(), usize::MAX)];
+    let mut index = FxHashMap::from_iter([(start.clone(), 0)]);
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        if cost > nodes[i].1 {
+            // Already reached more cheaply
+            continue;
+        }
+        if success(&nodes[i].0) {
+            let mut path = vec![];
+            let mut cur = i;
+            while cur != usize::MAX {
+                path.push(nodes[cur].0.clone());
+                cur = nodes[cur].2;
+            }
+            path.reverse();
+            return Some((path, cost));
+        }
+
+        for (next, step) in successors(&nodes[i].0) {
+            let next_cost = cost + step;
+            let j = match index.get(&next) {
+                Some(&j) if nodes[j].1 <= next_cost => continue,
+                Some(&j) => {
+                    nodes[j].1 = next_cost;
+                    nodes[j].2 = i;
+                    j
+                }
+                None => {
+                    index.insert(next.clone(), nodes.len());
+                    nodes.push((next.clone(), next_cost, i));
+                    nodes.len() - 1
+                }
+            };
+            heap.push(Reverse((next_cost + heuristic(&next), next_cost, j)));
+        }
+    }
+
+    None
+}
+
+/// Groups the nodes into connected components. `successors` should be symmetric.
+pub fn components<N, I>(
+    nodes: impl IntoIterator<Item = N>,
+    mut successors: impl FnMut(&N) -> I,
+) -> Vec<FxHashSet<N>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = FxHashSet::default();
+    let mut res = vec![];
+    for n in nodes {
+        if seen.contains(&n) {
+            continue;
+        }
+        let component = reachable([n], &mut successors);
+        seen.extend(component.iter().cloned());
+        res.push(component);
+    }
+    res
+}
+
+/// Cells reachable from the starts by orthogonal steps onto cells where `passable` holds.
+/// The starts themselves are always included.
+pub fn flood_fill<T>(
+    grid: &Grid<T>,
+    starts: impl IntoIterator<Item = Pos>,
+    passable: impl Fn(Pos, &T) -> bool,
+) -> Grid<bool> {
+    let mut filled = grid.map(|_, _| false);
+    for p in reachable(starts, |&p| {
+        grid.neighbours(p)
+            .filter(|&n| passable(n, &grid[n]))
+            .collect::<Vec<_>>()
+    }) {
+        filled[p] = true;
+    }
+    filled
+}
+
+/// Connected areas of orthogonally adjacent cells for which `same` holds
+pub fn grid_components<T>(grid: &Grid<T>, same: impl Fn(&T, &T) -> bool) -> Vec<FxHashSet<Pos>> {
+    components(grid.positions(), |&p| {
+        grid.neighbours(p)
+            .filter(|&n| same(&grid[p], &grid[n]))
+            .collect::<Vec<_>>()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "S.#.
+.##.
+...E";
+
+    fn maze() -> (Grid<char>, impl Fn(&Pos) -> Vec<Pos>) {
+        let grid = Grid::parse(MAZE, |c| c).unwrap();
+        let g = grid.clone();
+        let successors = move |&p: &Pos| g.neighbours(p).filter(|&n| g[n] != '#').collect();
+        (grid, successors)
+    }
+
+    #[test]
+    fn bfs_distances_and_path() {
+        let (_, successors) = maze();
+        let dist = bfs([(0, 0)], &successors);
+        assert_eq!(dist[&(3, 2)], 5);
+        assert_eq!(dist[&(3, 0)], 7);
+        assert!(!dist.contains_key(&(2, 0)));
+
+        let path = bfs_path((0, 0), &successors, |&p| p == (3, 2)).unwrap();
+        assert_eq!(path, [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (3, 2)]);
+        assert_eq!(bfs_path((0, 0), &successors, |&p| p == (2, 0)), None);
+    }
+
+    #[test]
+    fn weighted_paths() {
+        // Going through '9' directly is shorter but more expensive than the detour over '1's
+        let grid = Grid::parse("191\n111", |c| c.to_digit(10).unwrap()).unwrap();
+        let successors = |&p: &Pos| grid.neighbours(p).map(|n| (n, grid[n])).collect::<Vec<_>>();
+
+        let (path, cost) = dijkstra((0, 0), successors, |&p| p == (2, 0)).unwrap();
+        assert_eq!(cost, 4);
+        assert_eq!(path, [(0, 0), (0, 1), (1, 1), (2, 1), (2, 0)]);
+
+        let manhattan = |&(x, y): &Pos| (2 - x as u32) + y as u32;
+        let res = astar((0, 0), successors, manhattan, |&p| p == (2, 0));
+        assert_eq!(res.map(|r| r.1), Some(4));
+        assert_eq!(dijkstra((0, 0), successors, |_| false), None);
+    }
+
+    #[test]
+    fn fill_and_components() {
+        let (grid, successors) = maze();
+        let filled = flood_fill(&grid, [(0, 0)], |_, &c| c != '#');
+        assert_eq!(filled.iter().filter(|(_, &f)| f).count(), 9);
+        assert_eq!(reachable([(0, 0)], successors).len(), 9);
+
+        let mut sizes = grid_components(&grid, |a, b| (*a == '#') == (*b == '#'))
+            .iter()
+            .map(|c| c.len())
+            .collect::<Vec<_>>();
+        sizes.sort();
+        assert_eq!(sizes, [3, 9]);
+    }
+}
@@ -0,0 +1,190 @@
+//! Rectangular 2D map stored in a single row-major `Vec`.
+
+use std::{
+    error::Error,
+    ops::{Index, IndexMut},
+};
+
+/// `(x, y)`, with `y` growing downwards
+pub type Pos = (usize, usize);
+
+/// North, south, west, east as `(dx, dy)`
+pub const DIRS: [(isize, isize); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    /// Builds the grid from its rows, which must all have the same length
+    pub fn from_rows<R: IntoIterator<Item = T>>(
+        rows: impl IntoIterator<Item = R>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for row in rows {
+            let len = cells.len();
+            cells.extend(row);
+            let w = cells.len() - len;
+            if *width.get_or_insert(w) != w {
+                return Err(
+                    format!("Row {height} is {w} wide instead of {}", width.unwrap()).into(),
+                );
+            }
+            height += 1;
+        }
+
+        Ok(Self {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    /// One cell per character, one row per line
+    pub fn parse(input: &str, mut f: impl FnMut(char) -> T) -> Result<Self, Box<dyn Error>> {
+        Self::from_rows(
+            input
+                .lines()
+                .map(|l| l.chars().map(&mut f).collect::<Vec<_>>()),
+        )
+    }
+
+    /// Like [`Grid::parse`], failing on the first character `f` rejects
+    pub fn try_parse<E: Into<Box<dyn Error>>>(
+        input: &str,
+        mut f: impl FnMut(char) -> Result<T, E>,
+    ) -> Result<Self, Box<dyn Error>> {
+        let rows = input
+            .lines()
+            .map(|l| l.chars().map(&mut f).collect::<Result<Vec<_>, _>>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Into::into)?;
+        Self::from_rows(rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): Pos) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, (x, y): Pos) -> Option<&mut T> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// The position one step from `pos` in direction `(dx, dy)`, if it is still on the grid
+    pub fn step(&self, (x, y): Pos, (dx, dy): (isize, isize)) -> Option<Pos> {
+        let x = x.checked_add_signed(dx)?;
+        let y = y.checked_add_signed(dy)?;
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    /// The (up to 4) orthogonal neighbours of `pos`
+    pub fn neighbours(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        DIRS.into_iter().filter_map(move |d| self.step(pos, d))
+    }
+
+    /// Every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on 0, an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(Pos, &T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.iter().map(|(p, c)| f(p, c)).collect(),
+        }
+    }
+
+    /// Whether `pos` is on the outer border of the grid
+    pub fn is_edge(&self, (x, y): Pos) -> bool {
+        x == 0 || y == 0 || x + 1 == self.width || y + 1 == self.height
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the {}x{} grid", self.width, self.height))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        let (w, h) = (self.width, self.height);
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside the {w}x{h} grid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_index() {
+        let grid = Grid::parse("ab\ncd\nef", |c| c).unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid[(1, 2)], 'f');
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(
+            grid.rows().collect::<Vec<_>>(),
+            [['a', 'b'], ['c', 'd'], ['e', 'f']]
+        );
+        assert!(Grid::parse("ab\nc", |c| c).is_err());
+
+        let digits = |s| Grid::try_parse(s, |c| c.to_digit(10).ok_or("Not a digit"));
+        assert_eq!(digits("12\n34").unwrap()[(0, 1)], 3);
+        assert!(digits("12\n3x").is_err());
+    }
+
+    #[test]
+    fn neighbours_stay_on_grid() {
+        let grid = Grid::new(3, 2, 0);
+        assert_eq!(
+            grid.neighbours((0, 0)).collect::<Vec<_>>(),
+            [(0, 1), (1, 0)]
+        );
+        assert_eq!(
+            grid.neighbours((1, 1)).collect::<Vec<_>>(),
+            [(1, 0), (0, 1), (2, 1)]
+        );
+        assert!(grid.is_edge((2, 0)));
+        assert_eq!(grid.positions().count(), 6);
+    }
+}
@@ -0,0 +1,102 @@
+//! A fast hasher for the small integer keys of the inner loops: positions, directions and grids.
+//!
+//! This is the multiply-rotate hash of Firefox and rustc (FxHash). It is much cheaper than the
+//! default SipHash on short keys, but not resistant to inputs crafted to collide, which puzzle
+//! inputs aren't.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasherDefault, Hasher},
+};
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+impl FxHasher {
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for c in &mut chunks {
+            self.add(u64::from_le_bytes(c.try_into().unwrap()));
+        }
+        let mut rest = chunks.remainder();
+        if rest.len() >= 4 {
+            self.add(u32::from_le_bytes(rest[..4].try_into().unwrap()).into());
+            rest = &rest[4..];
+        }
+        for &b in rest {
+            self.add(b.into());
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(i.into());
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add(i.into());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(i.into());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+/// Create with `default()` or `with_capacity_and_hasher`, `new()` is only for the default hasher
+pub type FxHashSet<T> = HashSet<T, FxBuildHasher>;
+pub type FxHashMap<K, V> = HashMap<K, V, FxBuildHasher>;
+
+#[cfg(test)]
+mod tests {
+    use std::hash::BuildHasher;
+
+    use super::*;
+
+    #[test]
+    fn hashes_tuples_and_bytes() {
+        let hash = |v: &dyn Fn(&mut FxHasher)| {
+            let mut h = FxHasher::default();
+            v(&mut h);
+            h.finish()
+        };
+        let build = FxBuildHasher::default();
+        assert_eq!(build.hash_one((1i16, 2i16)), build.hash_one((1i16, 2i16)));
+        assert_ne!(build.hash_one((1i16, 2i16)), build.hash_one((2i16, 1i16)));
+        // Every byte of a write counts, whole words first
+        assert_ne!(
+            hash(&|h| h.write(b"abcdefghijklm")),
+            hash(&|h| h.write(b"abcdefghijkln"))
+        );
+        assert_eq!(
+            hash(&|h| h.write(b"abcdefgh")),
+            hash(&|h| h.write_u64(u64::from_le_bytes(*b"abcdefgh")))
+        );
+
+        let set = [(0, 1), (1, 0), (0, 1)]
+            .into_iter()
+            .collect::<FxHashSet<(usize, usize)>>();
+        assert_eq!(set.len(), 2);
+    }
+}
@@ -0,0 +1,230 @@
+//! Inputs too big to read into a `String`, e.g. stress tests of several gigabytes.
+//!
+//! An [`Input`] is either a memory-mapped file, whose lines borrow from the mapping, or a buffered
+//! reader, which reads one line at a time. The line-oriented days register a [`Streaming`] solver
+//! that goes through the lines once, so they solve such an input in constant memory.
+
+use std::{
+    borrow::Cow,
+    error::Error,
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+    str,
+};
+
+use memmap2::Mmap;
+
+use crate::{day1, day12, day15, day2, day4, day9};
+
+pub enum Input {
+    Mapped(Mmap),
+    Reader(Box<dyn BufRead>),
+}
+
+/// One line (or record) of an input
+pub type Record<'a> = Result<Cow<'a, str>, Box<dyn Error>>;
+
+impl Input {
+    /// Maps the file into memory. The file must not change while it is mapped.
+    pub fn mmap(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        let file = File::open(path)?;
+        // SAFETY: Nothing in this process writes to the file, other processes must not either
+        Ok(Self::Mapped(unsafe { Mmap::map(&file)? }))
+    }
+
+    pub fn buffered(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Ok(Self::from_reader(BufReader::new(File::open(path)?)))
+    }
+
+    pub fn from_reader(reader: impl BufRead + 'static) -> Self {
+        Self::Reader(Box::new(reader))
+    }
+
+    /// The lines without their `\n` or `\r\n`, like [`str::lines`]
+    pub fn lines(&mut self) -> Records<'_> {
+        self.records(b'\n')
+    }
+
+    /// The parts of the input between the `sep` bytes
+    pub fn records(&mut self, sep: u8) -> Records<'_> {
+        let source = match self {
+            Self::Mapped(map) => Source::Mapped(map),
+            Self::Reader(reader) => Source::Reader(reader.as_mut()),
+        };
+        Records { source, sep }
+    }
+}
+
+enum Source<'a> {
+    /// What is left of the mapping
+    Mapped(&'a [u8]),
+    Reader(&'a mut dyn BufRead),
+}
+
+/// Iterator over the records of an [`Input`]
+pub struct Records<'a> {
+    source: Source<'a>,
+    sep: u8,
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Record<'a>;
+
+    fn next(&mut self) -> Option<Record<'a>> {
+        let strip = |rec: &mut &[u8]| {
+            if self.sep == b'\n' {
+                *rec = rec.strip_suffix(b"\r").unwrap_or(rec);
+            }
+        };
+
+        match &mut self.source {
+            Source::Mapped(rest) => {
+                if rest.is_empty() {
+                    return None;
+                }
+                let (mut rec, tail) = match rest.iter().position(|&b| b == self.sep) {
+                    Some(i) => (&rest[..i], &rest[i + 1..]),
+                    None => (&rest[..], &[][..]),
+                };
+                *rest = tail;
+                strip(&mut rec);
+                Some(str::from_utf8(rec).map(Cow::Borrowed).map_err(Into::into))
+            }
+            Source::Reader(reader) => {
+                let mut buf = vec![];
+                match reader.read_until(self.sep, &mut buf) {
+                    Ok(0) => return None,
+                    Ok(_) => (),
+                    Err(e) => return Some(Err(e.into())),
+                }
+                let mut rec = buf.strip_suffix(&[self.sep]).unwrap_or(&buf);
+                strip(&mut rec);
+                let len = rec.len();
+                buf.truncate(len);
+                Some(String::from_utf8(buf).map(Cow::Owned).map_err(Into::into))
+            }
+        }
+    }
+}
+
+/// The lines of a `&str` as records, so the streaming solvers also work on whole inputs
+pub(crate) fn str_lines(input: &str) -> impl Iterator<Item = Record<'_>> {
+    input.lines().map(|l| Ok(Cow::Borrowed(l)))
+}
+
+/// A part that solves an [`Input`] in a single pass
+pub struct Streaming {
+    pub day: u32,
+    pub part: u32,
+    pub solve: fn(&mut Input) -> Result<String, Box<dyn Error>>,
+}
+
+pub const STREAMING: &[Streaming] = &[
+    Streaming {
+        day: 1,
+        part: 1,
+        solve: |i| Ok(day1::part1_lines(i.lines())?.to_string()),
+    },
+    Streaming {
+        day: 1,
+        part: 2,
+        solve: |i| Ok(day1::part2_lines(i.lines())?.to_string()),
+    },
+    Streaming {
+        day: 2,
+        part: 1,
+        solve: |i| Ok(day2::part1_lines(i.lines())?.to_string()),
+    },
+    Streaming {
+        day: 2,
+        part: 2,
+        solve: |i| Ok(day2::part2_lines(i.lines())?.to_string()),
+    },
+    Streaming {
+        day: 4,
+        part: 1,
+        solve: |i| Ok(day4::part1_lines(i.lines())?.to_string()),
+    },
+    Streaming {
+        day: 4,
+        part: 2,
+        solve: |i| Ok(day4::part2_lines(i.lines())?.to_string()),
+    },
+    Streaming {
+        day: 9,
+        part: 1,
+        solve: |i| Ok(day9::part1_lines(i.lines())?.to_string()),
+    },
+    Streaming {
+        day: 9,
+        part: 2,
+        solve: |i| Ok(day9::part2_lines(i.lines())?.to_string()),
+    },
+    Streaming {
+        day: 12,
+        part: 1,
+        solve: |i| Ok(day12::part1_lines(i.lines())?.to_string()),
+    },
+    Streaming {
+        day: 12,
+        part: 2,
+        solve: |i| Ok(day12::part2_lines(i.lines())?.to_string()),
+    },
+    Streaming {
+        day: 15,
+        part: 1,
+        solve: |i| Ok(day15::part1_steps(i.records(b','))?.to_string()),
+    },
+    Streaming {
+        day: 15,
+        part: 2,
+        solve: |i| Ok(day15::part2_steps(i.records(b','))?.to_string()),
+    },
+];
+
+pub fn find(day: u32, part: u32) -> Option<&'static Streaming> {
+    STREAMING.iter().find(|s| s.day == day && s.part == part)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Cursor};
+
+    use super::*;
+    use crate::test_server::temp_dir;
+
+    fn collect(mut input: Input, sep: u8) -> Vec<String> {
+        input
+            .records(sep)
+            .map(|r| r.unwrap().into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn mapped_and_buffered_agree() {
+        let path = temp_dir("mapped_and_buffered_agree").join("input.txt");
+        for text in ["a\nbc\r\n\nd", "a,b\n", ""] {
+            fs::write(&path, text).unwrap();
+            let lines = text.lines().collect::<Vec<_>>();
+            assert_eq!(collect(Input::mmap(&path).unwrap(), b'\n'), lines);
+            assert_eq!(collect(Input::buffered(&path).unwrap(), b'\n'), lines);
+            assert_eq!(
+                collect(Input::mmap(&path).unwrap(), b','),
+                collect(Input::from_reader(Cursor::new(text)), b',')
+            );
+        }
+        assert_eq!(
+            collect(Input::from_reader(Cursor::new("a,b\n")), b','),
+            ["a", "b\n"]
+        );
+    }
+
+    #[test]
+    fn invalid_utf8_is_an_error() {
+        let mut input = Input::from_reader(Cursor::new(b"ok\n\xff\n".to_vec()));
+        let mut lines = input.lines();
+        assert_eq!(lines.next().unwrap().unwrap(), "ok");
+        assert!(lines.next().unwrap().is_err());
+    }
+}
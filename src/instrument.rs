@@ -0,0 +1,167 @@
+//! Optional tracing of the solvers, behind the `instrument` feature.
+//!
+//! Every generator and part runs in a span carrying its day, part and variant (see
+//! [`Solution::solve`]), and solvers log with the `trace!` to `error!` macros below. Without the
+//! feature the macros expand to nothing and there is no subscriber, so default builds don't log.
+
+// Not every level is in use at any given time
+#![allow(unused_macros)]
+
+use crate::registry::Solution;
+
+macro_rules! trace {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "instrument")]
+        ::tracing::trace!($($arg)+);
+    }};
+}
+
+macro_rules! debug {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "instrument")]
+        ::tracing::debug!($($arg)+);
+    }};
+}
+
+macro_rules! info {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "instrument")]
+        ::tracing::info!($($arg)+);
+    }};
+}
+
+macro_rules! warn {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "instrument")]
+        ::tracing::warn!($($arg)+);
+    }};
+}
+
+macro_rules! error {
+    ($($arg:tt)+) => {{
+        #[cfg(feature = "instrument")]
+        ::tracing::error!($($arg)+);
+    }};
+}
+
+/// How log records are written to stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+/// Installs the stderr subscriber. `filter` takes `RUST_LOG` syntax, e.g. `debug` or
+/// `advent_of_code_2023::day12=trace`. Closing spans are logged with their timings.
+#[cfg(feature = "instrument")]
+pub fn init(format: Format, filter: &str) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::{self, IsTerminal};
+
+    use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
+
+    let builder = tracing_subscriber::fmt()
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .with_env_filter(EnvFilter::try_new(filter)?)
+        .with_span_events(FmtSpan::CLOSE);
+    let res = match format {
+        Format::Text => builder.try_init(),
+        Format::Json => builder.json().try_init(),
+    };
+    res.map_err(|e| e.to_string().into())
+}
+
+/// Runs the generator of `solution` in its span
+pub(crate) fn generator<R>(solution: &Solution, f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "instrument")]
+    let f = || {
+        let variant = solution.variant.unwrap_or_default();
+        tracing::info_span!(
+            "generator",
+            day = solution.day,
+            part = solution.part,
+            variant
+        )
+        .in_scope(f)
+    };
+    #[cfg(not(feature = "instrument"))]
+    let _ = solution;
+    f()
+}
+
+/// Runs the part of `solution` in its span
+pub(crate) fn part<R>(solution: &Solution, f: impl FnOnce() -> R) -> R {
+    #[cfg(feature = "instrument")]
+    let f = || {
+        let variant = solution.variant.unwrap_or_default();
+        tracing::info_span!("part", day = solution.day, part = solution.part, variant).in_scope(f)
+    };
+    #[cfg(not(feature = "instrument"))]
+    let _ = solution;
+    f()
+}
+
+#[cfg(all(test, feature = "instrument"))]
+mod tests {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use tracing_subscriber::fmt::MakeWriter;
+
+    use crate::registry;
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Buffer {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn spans_and_records() {
+        let buf = Buffer::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(buf.clone())
+            .with_max_level(tracing::Level::TRACE)
+            .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+            .json()
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let regex = registry::find(12, 1, Some("regex")).unwrap();
+            regex.solve("???.### 1,1,3").unwrap();
+        });
+
+        let out = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        let lines = out
+            .lines()
+            .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+            .collect::<Vec<_>>();
+        let spans = lines
+            .iter()
+            .filter_map(|l| l["span"]["name"].as_str())
+            .collect::<Vec<_>>();
+        assert!(spans.contains(&"generator"), "{out}");
+        assert!(spans.contains(&"part"), "{out}");
+        assert!(lines.iter().any(|l| l["span"]["variant"] == "regex"
+            && l["span"]["day"] == 12
+            && l["level"] == "TRACE"));
+    }
+}
@@ -0,0 +1,380 @@
+//! Statistics of a private leaderboard, from the JSON the site exports
+//! (`/2023/leaderboard/private/view/<id>.json`).
+
+use std::{collections::BTreeMap, error::Error, fmt::Write};
+
+use serde::{Deserialize, Serialize};
+
+/// The parts of the export we use
+#[derive(Debug, Deserialize)]
+pub struct Export {
+    pub event: String,
+    pub members: BTreeMap<String, Member>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    /// `None` for anonymous users
+    pub name: Option<String>,
+    pub local_score: u64,
+    /// Day to part to star, both keys are numbers
+    #[serde(default)]
+    pub completion_day_level: BTreeMap<String, BTreeMap<String, Star>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Star {
+    pub get_star_ts: i64,
+    /// Global order of the star, breaks ties between equal timestamps
+    #[serde(default)]
+    pub star_index: u64,
+}
+
+/// Day, part and star
+type Earned<'a> = (u32, u32, &'a Star);
+
+impl Member {
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("anonymous user #{}", self.id))
+    }
+
+    /// Every star as day, part and timestamp
+    fn stars(&self) -> Result<Vec<Earned<'_>>, Box<dyn Error>> {
+        let mut stars = vec![];
+        for (day, parts) in &self.completion_day_level {
+            for (part, star) in parts {
+                stars.push((day.parse()?, part.parse()?, star));
+            }
+        }
+        stars.sort_by_key(|&(day, part, s)| (s.get_star_ts, s.star_index, day, part));
+        Ok(stars)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub year: i32,
+    /// By recomputed local score, best first
+    pub members: Vec<MemberStats>,
+    pub days: Vec<DayRanking>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemberStats {
+    pub id: u64,
+    pub name: String,
+    pub local_score: u64,
+    /// The score in the export, differs from ours if the export is stale or the rules changed
+    pub exported_score: u64,
+    /// In the order they were earned
+    pub stars: Vec<StarTime>,
+    /// Seconds from part 1 to part 2, per day with both stars
+    pub deltas: Vec<(u32, i64)>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StarTime {
+    pub day: u32,
+    pub part: u32,
+    pub timestamp: i64,
+    /// Seconds since the puzzle unlocked
+    pub elapsed: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DayRanking {
+    pub day: u32,
+    /// By points earned on the day, then by the time of the last star
+    pub ranking: Vec<Ranked>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Ranked {
+    pub id: u64,
+    pub name: String,
+    pub points: u64,
+    pub stars: u32,
+    /// Seconds from the unlock to the last star of the day
+    pub elapsed: i64,
+}
+
+/// Seconds since the UNIX epoch at which a puzzle unlocks: midnight EST, 05:00 UTC
+pub fn unlock(year: i32, day: u32) -> i64 {
+    // Days from 1970-01-01 to December 1st, after Howard Hinnant's `days_from_civil`
+    let y = i64::from(year);
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * 9 + 2) / 5;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468 + i64::from(day) - 1;
+    days * 86_400 + 5 * 3600
+}
+
+pub fn parse(json: &str) -> Result<Export, Box<dyn Error>> {
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Recomputes the local scores: on every part the first of the `n` members gets `n` points, the
+/// second `n - 1` and so on
+pub fn analyze(export: &Export) -> Result<Report, Box<dyn Error>> {
+    let year = export
+        .event
+        .parse::<i32>()
+        .map_err(|e| format!("Invalid event {:?}: {e}", export.event))?;
+    let members = export.members.values().collect::<Vec<_>>();
+    let stars = members
+        .iter()
+        .map(|m| m.stars())
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Points per member, per day
+    let mut points = vec![BTreeMap::<u32, u64>::new(); members.len()];
+    let mut by_part = BTreeMap::<(u32, u32), Vec<(i64, u64, usize)>>::new();
+    for (i, stars) in stars.iter().enumerate() {
+        for &(day, part, s) in stars {
+            by_part
+                .entry((day, part))
+                .or_default()
+                .push((s.get_star_ts, s.star_index, i));
+        }
+    }
+    for ((day, _), mut got) in by_part {
+        got.sort_unstable();
+        for (rank, (_, _, i)) in got.into_iter().enumerate() {
+            *points[i].entry(day).or_default() += (members.len() - rank) as u64;
+        }
+    }
+
+    let mut stats = members
+        .iter()
+        .zip(&stars)
+        .zip(&points)
+        .map(|((m, stars), points)| {
+            let ts = |day, part| {
+                stars
+                    .iter()
+                    .find(|s| (s.0, s.1) == (day, part))
+                    .map(|s| s.2.get_star_ts)
+            };
+            MemberStats {
+                id: m.id,
+                name: m.display_name(),
+                local_score: points.values().sum(),
+                exported_score: m.local_score,
+                stars: stars
+                    .iter()
+                    .map(|&(day, part, s)| StarTime {
+                        day,
+                        part,
+                        timestamp: s.get_star_ts,
+                        elapsed: s.get_star_ts - unlock(year, day),
+                    })
+                    .collect(),
+                deltas: points
+                    .keys()
+                    .filter_map(|&day| Some((day, ts(day, 2)? - ts(day, 1)?)))
+                    .collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+    stats.sort_by(|a, b| b.local_score.cmp(&a.local_score).then(a.id.cmp(&b.id)));
+
+    let mut days = points.iter().flat_map(|p| p.keys()).collect::<Vec<_>>();
+    days.sort_unstable();
+    days.dedup();
+    let days = days
+        .into_iter()
+        .map(|&day| {
+            let mut ranking = members
+                .iter()
+                .zip(&stars)
+                .zip(&points)
+                .filter_map(|((m, stars), points)| {
+                    let today = stars.iter().filter(|s| s.0 == day).collect::<Vec<_>>();
+                    Some(Ranked {
+                        id: m.id,
+                        name: m.display_name(),
+                        points: *points.get(&day)?,
+                        stars: today.len() as u32,
+                        elapsed: today.iter().map(|s| s.2.get_star_ts).max()? - unlock(year, day),
+                    })
+                })
+                .collect::<Vec<_>>();
+            ranking.sort_by(|a, b| b.points.cmp(&a.points).then(a.elapsed.cmp(&b.elapsed)));
+            DayRanking { day, ranking }
+        })
+        .collect();
+
+    Ok(Report {
+        year,
+        members: stats,
+        days,
+    })
+}
+
+/// `h:mm:ss`, with as many hours as it takes
+pub fn format_seconds(secs: i64) -> String {
+    let sign = if secs < 0 { "-" } else { "" };
+    let secs = secs.unsigned_abs();
+    format!(
+        "{sign}{}:{:02}:{:02}",
+        secs / 3600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Scores, star timelines, part 1 to part 2 times and day rankings
+pub fn render(report: &Report) -> String {
+    let mut out = String::new();
+    let width = report
+        .members
+        .iter()
+        .map(|m| m.name.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    writeln!(out, "Local scores {}", report.year).unwrap();
+    for (i, m) in report.members.iter().enumerate() {
+        write!(out, "{:>3}. {:width$} {:>5}", i + 1, m.name, m.local_score).unwrap();
+        if m.local_score != m.exported_score {
+            write!(out, "  !! the export says {}", m.exported_score).unwrap();
+        }
+        out.push('\n');
+    }
+
+    writeln!(out, "\nStars (time since unlock)").unwrap();
+    for m in &report.members {
+        let stars = m
+            .stars
+            .iter()
+            .map(|s| format!("{}.{} {}", s.day, s.part, format_seconds(s.elapsed)))
+            .collect::<Vec<_>>();
+        let line = format!("{:width$}  {}", m.name, stars.join(", "));
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+
+    writeln!(out, "\nPart 1 to part 2").unwrap();
+    for m in &report.members {
+        let deltas = m
+            .deltas
+            .iter()
+            .map(|&(day, d)| format!("day {day} {}", format_seconds(d)))
+            .collect::<Vec<_>>();
+        let line = format!("{:width$}  {}", m.name, deltas.join(", "));
+        writeln!(out, "{}", line.trim_end()).unwrap();
+    }
+
+    for d in &report.days {
+        writeln!(out, "\nDay {}", d.day).unwrap();
+        for (i, r) in d.ranking.iter().enumerate() {
+            writeln!(
+                out,
+                "{:>3}. {:width$} {:>3} points, {} star(s) after {}",
+                i + 1,
+                r.name,
+                r.points,
+                r.stars,
+                format_seconds(r.elapsed)
+            )
+            .unwrap();
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"{
+        "event": "2023",
+        "owner_id": 1,
+        "members": {
+            "1": {
+                "id": 1, "name": "Ada", "stars": 3, "local_score": 8, "global_score": 0,
+                "last_star_ts": 1701493500,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1701407100, "star_index": 10},
+                        "2": {"get_star_ts": 1701407700, "star_index": 30}
+                    },
+                    "2": {"1": {"get_star_ts": 1701493500, "star_index": 50}}
+                }
+            },
+            "2": {
+                "id": 2, "name": null, "stars": 2, "local_score": 5, "global_score": 0,
+                "last_star_ts": 1701407400,
+                "completion_day_level": {
+                    "1": {
+                        "1": {"get_star_ts": 1701407100, "star_index": 5},
+                        "2": {"get_star_ts": 1701407400, "star_index": 20}
+                    }
+                }
+            },
+            "3": {
+                "id": 3, "name": "Cy", "stars": 0, "local_score": 0, "global_score": 0,
+                "last_star_ts": 0, "completion_day_level": {}
+            }
+        }
+    }"#;
+
+    #[test]
+    fn unlock_times() {
+        // 2023-12-01T05:00:00Z
+        assert_eq!(unlock(2023, 1), 1_701_406_800);
+        assert_eq!(unlock(2023, 25), 1_701_406_800 + 24 * 86_400);
+        // 2020-12-01T05:00:00Z, a leap year
+        assert_eq!(unlock(2020, 1), 1_606_798_800);
+    }
+
+    #[test]
+    fn recomputes_scores_and_rankings() {
+        let report = analyze(&parse(EXPORT).unwrap()).unwrap();
+        let scores = report
+            .members
+            .iter()
+            .map(|m| (m.name.as_str(), m.local_score, m.exported_score))
+            .collect::<Vec<_>>();
+        // Day 1: the anonymous user wins both parts, the tie on part 1 goes to the lower star index
+        assert_eq!(
+            scores,
+            [("Ada", 7, 8), ("anonymous user #2", 6, 5), ("Cy", 0, 0)]
+        );
+
+        let ada = &report.members[0];
+        assert_eq!(ada.deltas, [(1, 600)]);
+        assert_eq!(ada.stars[0].elapsed, 300);
+        assert_eq!(ada.stars[2].day, 2);
+
+        let day1 = &report.days[0].ranking;
+        assert_eq!(day1[0].name, "anonymous user #2");
+        assert_eq!(
+            (day1[0].points, day1[0].stars, day1[0].elapsed),
+            (6, 2, 600)
+        );
+        assert_eq!(report.days[1].ranking.len(), 1);
+
+        let text = render(&report);
+        assert!(
+            text.contains("  1. Ada                   7  !! the export says 8"),
+            "{text}"
+        );
+        assert!(text.contains("Ada                1.1 0:05:00, 1.2 0:15:00, 2.1 0:05:00"));
+        assert!(text.contains("anonymous user #2  day 1 0:05:00"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["days"][0]["ranking"][1]["name"], "Ada");
+    }
+
+    #[test]
+    fn formats_long_times() {
+        assert_eq!(format_seconds(0), "0:00:00");
+        assert_eq!(format_seconds(26 * 3600 + 61), "26:01:01");
+        assert_eq!(format_seconds(-5), "-0:00:05");
+    }
+}
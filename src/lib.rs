@@ -1,5 +1,41 @@
 use aoc_runner_derive::aoc_lib;
 
+pub mod api;
+pub mod assumptions;
+pub mod batch;
+pub mod config;
+pub mod dot;
+pub mod fixtures;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod hash;
+pub mod input;
+pub mod leaderboard;
+#[macro_use]
+pub mod instrument;
+pub mod memo;
+pub mod memory;
+pub mod minimize;
+pub mod parallel;
+pub mod progress;
+pub mod registry;
+pub mod report;
+pub mod sandbox;
+pub mod scaffold;
+pub mod scaling;
+#[cfg(feature = "server")]
+pub mod server;
+pub mod submit;
+
+#[cfg(test)]
+mod fuzz;
+#[cfg(test)]
+mod test_server;
+
+#[macro_use]
+mod examples;
+
 mod day1;
 mod day2;
 mod day3;
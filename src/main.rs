@@ -0,0 +1,508 @@
+use std::{
+    error::Error,
+    fs,
+    io::{self, IsTerminal},
+    panic,
+    path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use advent_of_code_2023::{
+    api::{self, Client},
+    assumptions, batch,
+    config::{Config, CONFIG_FILE},
+    dot, fixtures,
+    input::{self, Input},
+    leaderboard,
+    memory::{self, PeakAlloc},
+    minimize::{self, Failure},
+    parallel,
+    progress::{self, Renderer},
+    registry, report,
+    sandbox::{Outcome, Sandbox},
+    scaffold, scaling,
+    submit::{self, History},
+    YEAR,
+};
+use clap::{Parser, Subcommand};
+
+// Tracks the heap for the peak memory column of `report`
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc;
+
+#[derive(Parser)]
+#[command(about = "Advent of Code 2023 helper")]
+struct Cli {
+    /// Path to the config file
+    #[arg(long, default_value = CONFIG_FILE)]
+    config: String,
+
+    /// Worker threads of the parts that process rows/patterns/beams in parallel, 0 for all cores.
+    /// Needs the `parallel` feature.
+    #[arg(long, global = true)]
+    threads: Option<usize>,
+
+    /// Log filter in `RUST_LOG` syntax, e.g. `debug` or `advent_of_code_2023::day12=trace`.
+    /// Defaults to `RUST_LOG`, or `info`.
+    #[cfg(feature = "instrument")]
+    #[arg(long, global = true)]
+    log: Option<String>,
+    /// Write the logs as JSON lines
+    #[cfg(feature = "instrument")]
+    #[arg(long, global = true)]
+    log_json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(clap::Args)]
+struct SandboxArgs {
+    /// Seconds a single part may take
+    #[arg(long, default_value_t = Sandbox::default().timeout.as_secs_f64())]
+    timeout: f64,
+    /// Stack size of the solver threads in MiB
+    #[arg(long, default_value_t = Sandbox::default().stack_size / (1024 * 1024))]
+    stack_size: usize,
+}
+
+impl From<SandboxArgs> for Sandbox {
+    fn from(args: SandboxArgs) -> Self {
+        Self {
+            stack_size: args.stack_size * 1024 * 1024,
+            timeout: Duration::from_secs_f64(args.timeout),
+        }
+    }
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum FailureKind {
+    Panic,
+    Disagreement,
+    Mismatch,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve the cached inputs, continuing past parts that fail, panic or time out
+    Run {
+        /// Days to run, all of them if empty
+        days: Vec<u32>,
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: Option<u32>,
+        /// Variant to run instead of the one configured in `[variants]` (`default` for the
+        /// unnamed one). Needs a single day and `--part`.
+        #[arg(long, requires = "part")]
+        variant: Option<String>,
+        #[command(flatten)]
+        sandbox: SandboxArgs,
+    },
+    /// Download the puzzle input into the local cache (never re-fetches a cached input)
+    Download {
+        day: u32,
+        #[arg(long, default_value_t = YEAR)]
+        year: u32,
+    },
+    /// Submit an answer, skipping answers the submission history already knows about
+    Submit {
+        day: u32,
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: u32,
+        answer: String,
+        #[arg(long, default_value_t = YEAR)]
+        year: u32,
+    },
+    /// Create `src/dayN.rs` from a template and register it in `src/lib.rs`
+    Scaffold { day: u32 },
+    /// Solve every input in a directory with every variant and compare the answers
+    Batch {
+        dir: PathBuf,
+        /// Only solve this day; files without a day in their name are assumed to be for it
+        #[arg(long)]
+        day: Option<u32>,
+        #[command(flatten)]
+        sandbox: SandboxArgs,
+    },
+    /// Extract example inputs and answers from a saved puzzle page into test fixtures
+    Extract {
+        page: PathBuf,
+        #[arg(long, default_value = fixtures::FIXTURE_DIR)]
+        out: PathBuf,
+    },
+    /// Shrink a failing input while it keeps failing the same way and write the reproducer
+    Minimize {
+        day: u32,
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: u32,
+        input: PathBuf,
+        /// What has to keep happening
+        #[arg(long, value_enum, default_value_t = FailureKind::Panic)]
+        failure: FailureKind,
+        /// Variant that panics, or that is compared against the reference
+        #[arg(long)]
+        variant: Option<String>,
+        /// Variant giving the right answer, for `--failure mismatch`
+        #[arg(long)]
+        reference: Option<String>,
+        /// Defaults to the input path with a `.min` extension
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+        #[command(flatten)]
+        sandbox: SandboxArgs,
+    },
+    /// Check that an input has the hidden properties the solvers of its day rely on
+    Check {
+        day: u32,
+        /// Defaults to the cached input of the day
+        input: Option<PathBuf>,
+    },
+    /// Solve every cached input with every variant and write the results as a Markdown table
+    /// into the marked section of a file
+    Report {
+        #[arg(default_value = "README.md")]
+        file: PathBuf,
+        #[command(flatten)]
+        sandbox: SandboxArgs,
+    },
+    /// Solve a part in a single pass over the input, for inputs too big to read into memory
+    Stream {
+        day: u32,
+        #[arg(value_parser = clap::value_parser!(u32).range(1..=2))]
+        part: u32,
+        /// Defaults to the cached input of the day
+        input: Option<PathBuf>,
+        /// Read through a buffer instead of mapping the file into memory
+        #[arg(long)]
+        buffered: bool,
+    },
+    /// Write the graph of a day's input (days 8, 10 and 17) in Graphviz DOT format
+    Dot {
+        day: u32,
+        /// Defaults to the cached input of the day
+        input: Option<PathBuf>,
+        /// Day 17: only the states at most this many tiles from the start
+        #[arg(long, default_value_t = 4)]
+        radius: usize,
+        /// Defaults to stdout
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+    },
+    /// Star timelines, local scores and day rankings of a saved private leaderboard export
+    Leaderboard {
+        /// The JSON of `/2023/leaderboard/private/view/<id>.json`
+        file: PathBuf,
+        /// Print the statistics as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Time every variant on generated inputs of growing size and fit how the time grows
+    Scaling {
+        /// Days to measure, all of those with an input generator if empty
+        days: Vec<u32>,
+        /// Flag growth above `n^max_exponent` instead of the configured limit
+        #[arg(long)]
+        max_exponent: Option<f64>,
+        /// Number of sizes, each twice the previous one
+        #[arg(long, default_value_t = scaling::Options::default().steps)]
+        steps: u32,
+        #[command(flatten)]
+        sandbox: SandboxArgs,
+    },
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    let config = Config::load(&cli.config)?;
+    if let Some(n) = cli.threads {
+        if !cfg!(feature = "parallel") {
+            return Err("--threads needs the parallel feature".into());
+        }
+        parallel::set_threads(n);
+    }
+
+    #[cfg(feature = "instrument")]
+    {
+        use advent_of_code_2023::instrument::{self, Format};
+
+        let filter = cli
+            .log
+            .clone()
+            .or_else(|| std::env::var("RUST_LOG").ok())
+            .unwrap_or_else(|| "info".to_owned());
+        let format = if cli.log_json {
+            Format::Json
+        } else {
+            Format::Text
+        };
+        instrument::init(format, &filter)?;
+    }
+
+    match cli.command {
+        Command::Run {
+            days,
+            part,
+            variant,
+            sandbox,
+        } => {
+            let sandbox = Sandbox::from(sandbox);
+            if variant.is_some() && days.len() != 1 {
+                return Err("--variant needs a single day".into());
+            }
+            let days = if days.is_empty() {
+                registry::days()
+            } else {
+                days
+            };
+            // Progress of the long-running solvers, when someone is watching
+            let renderer = io::stderr().is_terminal().then(|| {
+                let renderer = Arc::new(Mutex::new(Renderer::new(
+                    io::stderr(),
+                    Duration::from_millis(200),
+                )));
+                let r = renderer.clone();
+                progress::set_reporter(move |p| {
+                    r.lock().unwrap_or_else(PoisonError::into_inner).update(p)
+                });
+                renderer
+            });
+
+            let mut failed = 0;
+            for day in days {
+                let path = api::input_path(api::INPUT_DIR, YEAR, day);
+                let input: Arc<str> = match fs::read_to_string(&path) {
+                    Ok(input) => input.into(),
+                    Err(e) => {
+                        println!("Day {day}: skipped, no input at {} ({e})", path.display());
+                        continue;
+                    }
+                };
+                for p in [1, 2]
+                    .into_iter()
+                    .filter(|p| part.is_none_or(|part| part == *p))
+                {
+                    if registry::variants(day, p).next().is_none() {
+                        continue;
+                    }
+                    let solution = config.solution(day, p, variant.as_deref())?;
+                    let outcome = sandbox.run(solution, input.clone());
+                    if let Some(r) = &renderer {
+                        r.lock().unwrap_or_else(PoisonError::into_inner).clear();
+                    }
+                    if !matches!(outcome, Outcome::Solved(_)) {
+                        failed += 1;
+                    }
+                    println!("{solution}: {outcome}");
+                }
+            }
+            progress::clear_reporter();
+            if failed > 0 {
+                return Err(format!("{failed} part(s) did not produce an answer").into());
+            }
+        }
+        Command::Download { day, year } => {
+            let client = Client::new(config.session()?);
+            let path = client.download_input(api::INPUT_DIR, year, day)?;
+            println!("{}", path.display());
+        }
+        Command::Submit {
+            day,
+            part,
+            answer,
+            year,
+        } => {
+            let client = Client::new(config.session()?);
+            let mut history = History::load(History::path_for(api::INPUT_DIR, year))?;
+            let res = submit::submit(&client, &mut history, year, day, part, &answer, |wait| {
+                eprintln!("Waiting {}s before submitting...", wait.as_secs());
+                thread::sleep(wait);
+            })?;
+
+            match res.wait {
+                Some(wait) => println!("{} (next answer in {}s)", res.verdict, wait.as_secs()),
+                None => println!("{}", res.verdict),
+            }
+        }
+        Command::Scaffold { day } => {
+            let path = scaffold::scaffold(".", day)?;
+            println!("{}", path.display());
+        }
+        Command::Batch { dir, day, sandbox } => {
+            let sandbox = Sandbox::from(sandbox);
+            let rows = batch::collect_inputs(dir, day)?
+                .into_iter()
+                .map(|(day, path)| batch::solve_all(day, path, &sandbox))
+                .collect::<Result<Vec<_>, _>>()?;
+            print!("{}", batch::render(&rows));
+        }
+        Command::Extract { page, out } => {
+            let found = fixtures::extract(&fs::read_to_string(page)?)?;
+            if found.is_empty() {
+                return Err("No examples with answers found on the page".into());
+            }
+            for path in fixtures::write(out, &found)? {
+                println!("{}", path.display());
+            }
+        }
+        Command::Minimize {
+            day,
+            part,
+            input,
+            failure,
+            variant,
+            reference,
+            out,
+            sandbox,
+        } => {
+            let sandbox = Sandbox::from(sandbox);
+            let find = |variant: Option<&str>| registry::select(day, part, variant);
+            let failure = match failure {
+                FailureKind::Panic => Failure::Panic(find(variant.as_deref())?),
+                FailureKind::Disagreement => Failure::Disagreement { day, part },
+                FailureKind::Mismatch => Failure::Mismatch {
+                    solution: find(variant.as_deref())?,
+                    reference: find(reference.as_deref())?,
+                },
+            };
+
+            let text = fs::read_to_string(&input)?;
+            // Most attempts panic on purpose, keep the messages off the terminal
+            panic::set_hook(Box::new(|_| {}));
+            let mut attempts = 0;
+            let res = minimize::minimize(&text, |s| {
+                attempts += 1;
+                failure.reproduces(&sandbox, s)
+            });
+            drop(panic::take_hook());
+            let res = res?;
+
+            let out = out.unwrap_or_else(|| input.with_extension("min"));
+            fs::write(&out, &res)?;
+            println!(
+                "{} ({} -> {} bytes after {attempts} attempts)",
+                out.display(),
+                text.len(),
+                res.len()
+            );
+        }
+        Command::Check { day, input } => {
+            let input = input.unwrap_or_else(|| api::input_path(api::INPUT_DIR, YEAR, day));
+            let results = assumptions::check(day, &fs::read_to_string(input)?);
+            if results.is_empty() {
+                println!("Day {day} makes no assumptions about its input");
+            }
+
+            let mut failed = 0;
+            for (a, res) in results {
+                match res {
+                    Ok(()) => println!("ok     Part {}: {}", a.part, a.description),
+                    Err(e) => {
+                        failed += 1;
+                        println!("FAILED Part {}: {}\n       {e}", a.part, a.description);
+                    }
+                }
+            }
+            if failed > 0 {
+                return Err(format!(
+                    "{failed} assumption(s) failed, those answers can't be trusted"
+                )
+                .into());
+            }
+        }
+        Command::Report { file, sandbox } => {
+            let sandbox = Sandbox::from(sandbox);
+            let history = History::load(History::path_for(api::INPUT_DIR, YEAR))?;
+            let days = registry::days()
+                .into_iter()
+                .map(|day| {
+                    let path = api::input_path(api::INPUT_DIR, YEAR, day);
+                    let input = fs::read_to_string(path).ok().map(Arc::from);
+                    report::run_day(day, input, &config, &history, &sandbox)
+                })
+                .collect::<Vec<_>>();
+            report::update(&file, &report::render(&days))?;
+            println!("{}", file.display());
+        }
+        Command::Stream {
+            day,
+            part,
+            input,
+            buffered,
+        } => {
+            let solver = input::find(day, part)
+                .ok_or_else(|| format!("Day {day} part {part} has no streaming solver"))?;
+            let path = input.unwrap_or_else(|| api::input_path(api::INPUT_DIR, YEAR, day));
+            let mut input = if buffered {
+                Input::buffered(path)?
+            } else {
+                Input::mmap(path)?
+            };
+
+            let start = Instant::now();
+            let (answer, peak) = memory::measure(|| (solver.solve)(&mut input));
+            let peak = peak.map_or_else(|| "-".to_owned(), memory::format_bytes);
+            println!("{} ({:?}, {peak} peak heap)", answer?, start.elapsed());
+        }
+        Command::Dot {
+            day,
+            input,
+            radius,
+            out,
+        } => {
+            let input = input.unwrap_or_else(|| api::input_path(api::INPUT_DIR, YEAR, day));
+            let graph = dot::export(day, &fs::read_to_string(input)?, radius)?;
+            match out {
+                Some(out) => fs::write(out, graph)?,
+                None => print!("{graph}"),
+            }
+        }
+        Command::Leaderboard { file, json } => {
+            let report = leaderboard::analyze(&leaderboard::parse(&fs::read_to_string(file)?)?)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print!("{}", leaderboard::render(&report));
+            }
+        }
+        Command::Scaling {
+            days,
+            max_exponent,
+            steps,
+            sandbox,
+        } => {
+            let sandbox = Sandbox::from(sandbox);
+            let max_exponent = max_exponent.unwrap_or(config.scaling.max_exponent);
+            let options = scaling::Options {
+                steps,
+                ..Default::default()
+            };
+            if let Some(day) = days
+                .iter()
+                .find(|d| scaling::WORKLOADS.iter().all(|w| w.day != **d))
+            {
+                return Err(format!("Day {day} has no input generator").into());
+            }
+
+            let mut flagged = 0;
+            for w in scaling::WORKLOADS
+                .iter()
+                .filter(|w| days.is_empty() || days.contains(&w.day))
+            {
+                let curves = scaling::measure(w, &options, &sandbox);
+                flagged += curves
+                    .iter()
+                    .filter(|c| c.exponent().is_some_and(|k| k > max_exponent))
+                    .count();
+                print!("{}", scaling::render(&curves, max_exponent));
+            }
+            if flagged > 0 {
+                return Err(
+                    format!("{flagged} variant(s) grow faster than n^{max_exponent}").into(),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
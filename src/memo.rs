@@ -0,0 +1,90 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// A memoization cache owned by a single solve call.
+///
+/// Unlike `#[cached]`, nothing is shared between calls: the cache lives as long as
+/// the `Memo` value and its memory is reclaimed when it is dropped (or cleared).
+#[derive(Debug)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    hits: usize,
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            cache: HashMap::new(),
+            hits: 0,
+        }
+    }
+
+    /// Returns the cached value for `key`, computing and storing it with `f` on a miss.
+    ///
+    /// `f` gets the cache back, so recursive functions can pass it down to themselves.
+    pub fn get_or_insert_with(&mut self, key: K, f: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(v) = self.cache.get(&key) {
+            self.hits += 1;
+            return v.clone();
+        }
+
+        let v = f(self);
+        self.cache.insert(key, v.clone());
+        v
+    }
+
+    /// Number of cached entries
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Number of lookups answered from the cache
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.hits = 0;
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fib(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+        memo.get_or_insert_with(n, |memo| match n {
+            0 | 1 => n,
+            n => fib(n - 1, memo) + fib(n - 2, memo),
+        })
+    }
+
+    #[test]
+    fn memoizes_recursive_calls() {
+        let mut memo = Memo::new();
+        assert_eq!(fib(90, &mut memo), 2880067194370816120);
+        assert_eq!(memo.len(), 91);
+        assert_eq!(memo.hits(), 88);
+    }
+
+    #[test]
+    fn clear_empties_cache() {
+        let mut memo = Memo::new();
+        fib(10, &mut memo);
+        assert!(!memo.is_empty());
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.hits(), 0);
+    }
+}
@@ -0,0 +1,106 @@
+//! Heap usage tracking for peak memory measurements.
+//!
+//! Binaries opt in by installing the allocator:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOC: PeakAlloc = PeakAlloc;
+//! ```
+//!
+//! Counting is process-wide, so allocations of other threads running at the same time are
+//! included in a measurement.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+/// The system allocator, counting the bytes in use and their peak
+pub struct PeakAlloc;
+
+impl PeakAlloc {
+    fn add(size: usize) {
+        let now = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(now, Ordering::Relaxed);
+        INSTALLED.store(true, Ordering::Relaxed);
+    }
+
+    fn sub(size: usize) {
+        CURRENT.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            Self::add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            Self::add(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        Self::sub(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            Self::sub(layout.size());
+            Self::add(new_size);
+        }
+        new
+    }
+}
+
+/// Runs `f` and returns the most heap it had allocated at once, on top of what was in use before.
+/// `None` when [`PeakAlloc`] is not the global allocator.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Option<usize>) {
+    let base = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    let res = f();
+    let peak = PEAK.load(Ordering::Relaxed).saturating_sub(base);
+    (res, INSTALLED.load(Ordering::Relaxed).then_some(peak))
+}
+
+/// `1.5 MiB` and the like
+pub fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+// Measuring needs `PeakAlloc` as the global allocator, see `tests/memory.rs`
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_sizes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 << 20), "3.0 MiB");
+    }
+}
@@ -0,0 +1,184 @@
+//! Delta debugging: shrinks a failing input while it keeps failing.
+//!
+//! Inputs are cut down in whole blocks (separated by blank lines), then lines, then character
+//! columns (grid columns for grid inputs), repeating until none of them removes anything.
+
+use std::{collections::HashSet, error::Error, sync::Arc};
+
+use crate::{
+    registry::{self, Solution},
+    sandbox::{Outcome, Sandbox},
+};
+
+/// The failure a reproducer has to keep showing
+#[derive(Debug, Clone, Copy)]
+pub enum Failure {
+    /// The solution panics
+    Panic(&'static Solution),
+    /// Some variants of the part give different answers
+    Disagreement { day: u32, part: u32 },
+    /// The solution answers differently than the reference
+    Mismatch {
+        solution: &'static Solution,
+        reference: &'static Solution,
+    },
+}
+
+impl Failure {
+    /// Whether `input` still shows the failure. Errors and timeouts don't count, so the input
+    /// can't drift into being rejected by the parser instead.
+    pub fn reproduces(&self, sandbox: &Sandbox, input: &str) -> bool {
+        let input: Arc<str> = input.into();
+        match *self {
+            Self::Panic(s) => matches!(sandbox.run(s, input), Outcome::Panicked(_)),
+            Self::Disagreement { day, part } => {
+                let answers = registry::variants(day, part)
+                    .filter_map(|s| match sandbox.run(s, input.clone()) {
+                        Outcome::Solved(a) => Some(a.answer),
+                        _ => None,
+                    })
+                    .collect::<HashSet<_>>();
+                answers.len() > 1
+            }
+            Self::Mismatch {
+                solution,
+                reference,
+            } => {
+                let res = sandbox.run(solution, input.clone());
+                let expected = sandbox.run(reference, input);
+                matches!((res.answer(), expected.answer()), (Some(a), Some(b)) if a != b)
+            }
+        }
+    }
+}
+
+/// Smallest input found for which `fails` still holds
+pub fn minimize(
+    input: &str,
+    mut fails: impl FnMut(&str) -> bool,
+) -> Result<String, Box<dyn Error>> {
+    if !fails(input) {
+        return Err("The input doesn't fail to begin with".into());
+    }
+
+    let mut input = input.to_owned();
+    loop {
+        let before = input.len();
+        input = by_separator(&input, "\n\n", &mut fails);
+        input = by_separator(&input, "\n", &mut fails);
+        input = by_columns(&input, &mut fails);
+        if input.len() == before {
+            return Ok(input);
+        }
+    }
+}
+
+fn by_separator(input: &str, sep: &str, fails: &mut impl FnMut(&str) -> bool) -> String {
+    let parts = input.split(sep).collect::<Vec<_>>();
+    ddmin(parts, |parts| fails(&parts.join(sep))).join(sep)
+}
+
+fn by_columns(input: &str, fails: &mut impl FnMut(&str) -> bool) -> String {
+    let lines = input
+        .lines()
+        .map(|l| l.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let width = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+    let render = |columns: &[usize]| {
+        lines
+            .iter()
+            .map(|l| columns.iter().filter_map(|&c| l.get(c)).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    let columns = ddmin((0..width).collect(), |columns| fails(&render(columns)));
+    render(&columns)
+}
+
+/// Removes chunks of `items`, halving the chunk size whenever no chunk can be removed
+fn ddmin<T: Clone>(mut items: Vec<T>, mut fails: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut chunk = (items.len() / 2).max(1);
+    while !items.is_empty() {
+        let mut removed = false;
+        let mut i = 0;
+        while i < items.len() {
+            let end = (i + chunk).min(items.len());
+            let candidate = [&items[..i], &items[end..]].concat();
+            if fails(&candidate) {
+                items = candidate;
+                removed = true;
+            } else {
+                i += chunk;
+            }
+        }
+
+        if !removed {
+            if chunk == 1 {
+                break;
+            }
+            chunk /= 2;
+        }
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_failing_line() {
+        let input = (0..100)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let res = minimize(&input, |s| {
+            s.lines().any(|l| l == "42") && s.lines().any(|l| l == "7")
+        });
+        assert_eq!(res.unwrap(), "7\n42");
+        assert!(minimize("1\n2", |s| s.contains('3')).is_err());
+    }
+
+    #[test]
+    fn removes_blocks_and_columns() {
+        let input = "...\n.#.\n...\n\n...\n...";
+        let res = minimize(input, |s| s.contains('#'));
+        assert_eq!(res.unwrap(), "#");
+
+        // A grid that has to stay rectangular keeps whole columns
+        let input = "....\n.#..\n....";
+        let rectangular = |s: &str| {
+            let lens = s.lines().map(str::len).collect::<HashSet<_>>();
+            lens.len() == 1 && s.lines().count() >= 2 && s.contains('#')
+        };
+        assert_eq!(minimize(input, rectangular).unwrap(), "#\n.");
+    }
+
+    #[test]
+    fn minimizes_mismatches() {
+        let sandbox = Sandbox::default();
+        // Day 0 is a test fixture whose `off_by_one` variant is wrong whenever there is a `#`
+        let failure = Failure::Mismatch {
+            solution: registry::find(0, 1, Some("off_by_one")).unwrap(),
+            reference: registry::find(0, 1, None).unwrap(),
+        };
+        let input = "....\n..#.\n....\n\nA block without any";
+        let res = minimize(input, |s| failure.reproduces(&sandbox, s)).unwrap();
+        assert_eq!(res, "#");
+
+        // Errors are not the failure being looked for
+        let failure = Failure::Panic(registry::find(9, 1, None).unwrap());
+        assert!(!failure.reproduces(&sandbox, "1 2 x"));
+    }
+
+    #[test]
+    fn minimizes_disagreements() {
+        let sandbox = Sandbox::default();
+        let failure = Failure::Disagreement { day: 0, part: 1 };
+        let input = "#..#\n....\n\n.#..";
+        let res = minimize(input, |s| failure.reproduces(&sandbox, s)).unwrap();
+        assert_eq!(res, "#");
+        assert!(!failure.reproduces(&sandbox, "...."));
+    }
+}
@@ -0,0 +1,95 @@
+//! Data-parallel helpers for parts that process independent items (rows, patterns, beams).
+//!
+//! Threads are only used with the `parallel` feature. Without it, or with the thread count set to 1
+//! (`--threads 1`), everything runs on the calling thread. Results always come back in input
+//! order, so reductions over them are deterministic regardless of scheduling.
+
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+/// 0 means "use all available cores"
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// Stack of the workers `map` spawns from this thread, `None` for the default of `std`
+    static STACK_SIZE: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Overrides the number of worker threads, 0 restores the default
+pub fn set_threads(n: usize) {
+    THREADS.store(n, Ordering::Relaxed);
+}
+
+/// Gives the workers that `map` spawns from the current thread `bytes` of stack, so recursive
+/// solvers get as much on them as on the calling thread. The sandbox sets it on its solver threads.
+pub fn set_stack_size(bytes: usize) {
+    STACK_SIZE.set(Some(bytes));
+}
+
+/// Number of worker threads `map` will use
+pub fn threads() -> usize {
+    if !cfg!(feature = "parallel") {
+        return 1;
+    }
+
+    match THREADS.load(Ordering::Relaxed) {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
+/// Applies `f` to every item, possibly on several threads. The output is in input order.
+pub fn map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let threads = threads().min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    // Items can take wildly different time (e.g. day12 rows), so workers pull them one by one
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+    let stack_size = STACK_SIZE.get();
+    thread::scope(|s| {
+        for _ in 0..threads {
+            let mut builder = thread::Builder::new();
+            if let Some(bytes) = stack_size {
+                builder = builder.stack_size(bytes);
+            }
+            builder
+                .spawn_scoped(s, || loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(i) else { break };
+                    let r = f(item);
+                    results.lock().unwrap().push((i, r));
+                })
+                .expect("failed to spawn a worker thread");
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_unstable_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_keeps_order() {
+        let items = (0..1000u64).collect::<Vec<_>>();
+        let res = map(&items, |i| i * i);
+        assert_eq!(res, items.iter().map(|i| i * i).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn map_empty() {
+        assert!(map(&[] as &[u8], |_| 0).is_empty());
+    }
+}
@@ -0,0 +1,170 @@
+//! Progress of long-running solvers.
+//!
+//! Solvers call [`report`] with the work done so far and the total. Nothing happens unless a
+//! reporter is installed with [`set_reporter`], as the `run` command does with a [`Renderer`]
+//! drawing a progress line on stderr, so library users pay for an atomic load per call.
+
+use std::{
+    io::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// What is being counted, e.g. `rows`
+    pub unit: &'static str,
+    pub done: u64,
+    /// `0` when the end isn't known in advance
+    pub total: u64,
+}
+
+type Reporter = Box<dyn FnMut(Progress) + Send>;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static REPORTER: Mutex<Option<Reporter>> = Mutex::new(None);
+
+/// Tells the reporter, if any, that `done` of `total` units of work are finished. Pass a `total`
+/// of `0` when only the work done so far is known.
+pub fn report(unit: &'static str, done: u64, total: u64) {
+    if !ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    let mut reporter = REPORTER.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(r) = reporter.as_mut() {
+        r(Progress { unit, done, total });
+    }
+}
+
+/// Sends every [`report`] of any thread to `reporter`, replacing the previous one
+pub fn set_reporter(reporter: impl FnMut(Progress) + Send + 'static) {
+    *REPORTER.lock().unwrap_or_else(PoisonError::into_inner) = Some(Box::new(reporter));
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn clear_reporter() {
+    ENABLED.store(false, Ordering::Relaxed);
+    *REPORTER.lock().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Draws the progress as a single line that is redrawn at most every `interval`, e.g.
+/// `rows 120/1000 (12%), ETA 0:37`, or just `rows 120` without a total
+pub struct Renderer<W: Write> {
+    out: W,
+    interval: Duration,
+    /// Start of the current task and the last progress of it
+    task: Option<(Instant, Progress)>,
+    drawn: Option<Instant>,
+}
+
+impl<W: Write> Renderer<W> {
+    pub fn new(out: W, interval: Duration) -> Self {
+        Self {
+            out,
+            interval,
+            task: None,
+            drawn: None,
+        }
+    }
+
+    pub fn update(&mut self, p: Progress) {
+        self.update_at(p, Instant::now());
+    }
+
+    fn update_at(&mut self, p: Progress, now: Instant) {
+        // Another unit or fewer units done than before: a new task started
+        let start = match self.task {
+            Some((start, last)) if last.unit == p.unit && last.done <= p.done => start,
+            _ => now,
+        };
+        self.task = Some((start, p));
+        if self.drawn.is_some_and(|d| now - d < self.interval) {
+            return;
+        }
+        self.drawn = Some(now);
+
+        let mut line = format!("{} {}", p.unit, p.done);
+        if p.total > 0 {
+            line += &format!("/{}", p.total);
+            let ratio = p.done as f64 / p.total as f64;
+            line += &format!(" ({:.0}%)", ratio * 100.0);
+            if p.done > 0 {
+                let eta = (now - start).mul_f64((1.0 - ratio).max(0.0) / ratio);
+                line += &format!(", ETA {}", format_eta(eta));
+            }
+        }
+        // Clear the rest of the previous line
+        let _ = write!(self.out, "\r{line}\x1b[K");
+        let _ = self.out.flush();
+    }
+
+    /// Removes the line, e.g. before printing the answer
+    pub fn clear(&mut self) {
+        if self.drawn.take().is_some() {
+            let _ = write!(self.out, "\r\x1b[K");
+            let _ = self.out.flush();
+        }
+        self.task = None;
+    }
+}
+
+/// `m:ss`, or `h:mm:ss` from an hour on
+fn format_eta(eta: Duration) -> String {
+    let secs = eta.as_secs();
+    match secs / 3600 {
+        0 => format!("{}:{:02}", secs / 60, secs % 60),
+        h => format!("{h}:{:02}:{:02}", secs / 60 % 60, secs % 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throttles_and_estimates() {
+        let mut r = Renderer::new(vec![], Duration::from_millis(100));
+        let t0 = Instant::now();
+        let at = |ms| t0 + Duration::from_millis(ms);
+        let p = |done| Progress {
+            unit: "rows",
+            done,
+            total: 1000,
+        };
+
+        r.update_at(p(0), at(0));
+        // Too soon after the previous line
+        r.update_at(p(10), at(50));
+        r.update_at(p(100), at(2000));
+        r.clear();
+
+        let out = String::from_utf8(r.out).unwrap();
+        assert_eq!(
+            out,
+            "\rrows 0/1000 (0%)\x1b[K\rrows 100/1000 (10%), ETA 0:18\x1b[K\r\x1b[K"
+        );
+        assert_eq!(format_eta(Duration::from_secs(3725)), "1:02:05");
+    }
+
+    #[test]
+    fn unknown_total() {
+        let mut r = Renderer::new(vec![], Duration::ZERO);
+        let p = Progress {
+            unit: "cycles",
+            done: 42,
+            total: 0,
+        };
+        r.update(p);
+
+        assert_eq!(String::from_utf8(r.out).unwrap(), "\rcycles 42\x1b[K");
+    }
+
+    #[test]
+    fn no_op_without_reporter() {
+        // Must not block or panic when nothing listens
+        report("rows", 1, 2);
+    }
+}
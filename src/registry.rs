@@ -0,0 +1,304 @@
+//! Every `#[aoc]` solution in the crate, so tools can list and run them by day/part/variant.
+
+use std::{
+    error::Error,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use aoc_runner::{ArcStr, Runner};
+
+use crate::*;
+
+/// Name of the unnamed variant wherever one has to be spelled out
+pub const DEFAULT: &str = "default";
+
+/// Generates the input of a solution and wraps it in a runner (the `Factory` methods of aoc-runner)
+pub type MakeRunner = fn(ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>>;
+
+/// Runs a solution whose generator returns data borrowed from the input. aoc-runner's runners
+/// can't hold such data, so these run both phases themselves through [`Phases`] and are only
+/// registered here, not with `#[aoc]`.
+pub type SolveBorrowed = fn(&str, &mut Phases) -> Result<String, Box<dyn Error>>;
+
+#[derive(Clone, Copy)]
+pub enum Make {
+    Runner(MakeRunner),
+    Borrowed(SolveBorrowed),
+}
+
+#[derive(Clone, Copy)]
+pub struct Solution {
+    pub day: u32,
+    pub part: u32,
+    /// Name given in `#[aoc(dayN, partN, name)]`, `None` for the unnamed one
+    pub variant: Option<&'static str>,
+    pub make: Make,
+}
+
+/// Output of a single run
+#[derive(Debug, Clone)]
+pub struct Answer {
+    pub answer: String,
+    pub generator: Duration,
+    pub runner: Duration,
+}
+
+/// Runs the generator and the part of a solution in their spans and times them
+pub struct Phases<'s> {
+    solution: &'s Solution,
+    generator: Duration,
+    runner: Duration,
+}
+
+impl Phases<'_> {
+    pub fn generate<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let res = instrument::generator(self.solution, f);
+        self.generator = start.elapsed();
+        res
+    }
+
+    pub fn run<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        let start = Instant::now();
+        let res = instrument::part(self.solution, f);
+        self.runner = start.elapsed();
+        res
+    }
+}
+
+impl Solution {
+    const fn new(day: u32, part: u32, variant: Option<&'static str>, make: MakeRunner) -> Self {
+        Self {
+            day,
+            part,
+            variant,
+            make: Make::Runner(make),
+        }
+    }
+
+    const fn borrowed(
+        day: u32,
+        part: u32,
+        variant: Option<&'static str>,
+        solve: SolveBorrowed,
+    ) -> Self {
+        Self {
+            day,
+            part,
+            variant,
+            make: Make::Borrowed(solve),
+        }
+    }
+
+    /// Runs the generator and the solver on `input`
+    pub fn solve(&self, input: &str) -> Result<Answer, Box<dyn Error>> {
+        let mut phases = Phases {
+            solution: self,
+            generator: Duration::ZERO,
+            runner: Duration::ZERO,
+        };
+        let answer = match self.make {
+            Make::Runner(make) => {
+                let runner = phases.generate(|| make(ArcStr::from(input)))?;
+                phases.run(|| runner.try_run())?.to_string()
+            }
+            Make::Borrowed(solve) => solve(input, &mut phases)?,
+        };
+
+        Ok(Answer {
+            answer,
+            generator: phases.generator,
+            runner: phases.runner,
+        })
+    }
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Day {} - Part {}", self.day, self.part)?;
+        if let Some(v) = self.variant {
+            write!(f, " - {v}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Solution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+pub static SOLUTIONS: &[Solution] = &[
+    Solution::new(1, 1, None, Factory::day1_part1),
+    Solution::new(1, 2, None, Factory::day1_part2),
+    Solution::new(2, 1, None, Factory::day2_part1),
+    Solution::new(2, 2, None, Factory::day2_part2),
+    Solution::new(3, 1, None, Factory::day3_part1),
+    Solution::new(3, 2, None, Factory::day3_part2),
+    Solution::new(4, 1, None, Factory::day4_part1),
+    Solution::new(4, 2, None, Factory::day4_part2),
+    Solution::new(5, 1, None, Factory::day5_part1),
+    Solution::new(5, 2, None, Factory::day5_part2),
+    Solution::new(6, 1, None, Factory::day6_part1),
+    Solution::new(6, 2, None, Factory::day6_part2),
+    Solution::new(6, 2, Some("quadratic"), Factory::day6_part2_quadratic),
+    Solution::new(7, 1, None, Factory::day7_part1),
+    Solution::new(7, 2, None, Factory::day7_part2),
+    Solution::borrowed(8, 1, None, |input, phases| {
+        let network = phases.generate(|| day8::parse(input))?;
+        Ok(phases.run(|| day8::part1(&network))?.to_string())
+    }),
+    Solution::borrowed(8, 2, None, |input, phases| {
+        let network = phases.generate(|| day8::parse(input))?;
+        Ok(phases.run(|| day8::part2(&network))?.to_string())
+    }),
+    Solution::new(9, 1, None, Factory::day9_part1),
+    Solution::new(9, 2, None, Factory::day9_part2),
+    Solution::new(10, 1, None, Factory::day10_part1),
+    Solution::new(10, 2, None, Factory::day10_part2),
+    Solution::new(10, 2, Some("shoelace"), Factory::day10_part2_shoelace),
+    Solution::new(11, 1, None, Factory::day11_part1),
+    Solution::new(11, 2, None, Factory::day11_part2),
+    Solution::new(12, 1, None, Factory::day12_part1),
+    Solution::new(12, 1, Some("regex"), Factory::day12_part1_regex),
+    Solution::new(12, 2, None, Factory::day12_part2),
+    Solution::new(13, 1, None, Factory::day13_part1),
+    Solution::new(13, 2, None, Factory::day13_part2),
+    Solution::new(14, 1, None, Factory::day14_part1),
+    Solution::new(14, 2, None, Factory::day14_part2),
+    Solution::borrowed(15, 1, None, |input, phases| {
+        let steps = phases.generate(|| day15::parse(input))?;
+        Ok(phases.run(|| day15::part1(&steps))?.to_string())
+    }),
+    Solution::borrowed(15, 2, None, |input, phases| {
+        let steps = phases.generate(|| day15::parse(input))?;
+        Ok(phases.run(|| day15::part2(&steps))?.to_string())
+    }),
+    Solution::new(16, 1, None, Factory::day16_part1),
+    Solution::new(16, 1, Some("recursive"), Factory::day16_part1_recursive),
+    Solution::new(16, 2, None, Factory::day16_part2),
+    Solution::new(16, 2, Some("recursive"), Factory::day16_part2_recursive),
+    Solution::new(17, 1, None, Factory::day17_part1),
+    Solution::new(17, 2, None, Factory::day17_part2),
+];
+
+/// Day 0 only exists in tests: part 1 counts the `#`s, and its `off_by_one` variant gets it wrong
+/// whenever there are any. Tests of the tools that catch variants disagreeing use it, so they don't
+/// depend on a real variant being wrong.
+#[cfg(test)]
+static FIXTURES: &[Solution] = &[
+    Solution::borrowed(0, 1, None, |input, phases| {
+        Ok(phases.run(|| input.matches('#').count()).to_string())
+    }),
+    Solution::borrowed(0, 1, Some("off_by_one"), |input, phases| {
+        let n = phases.run(|| input.matches('#').count());
+        Ok((n + usize::from(n > 0)).to_string())
+    }),
+];
+#[cfg(not(test))]
+static FIXTURES: &[Solution] = &[];
+
+/// Every solution, [`SOLUTIONS`] and in tests the fixtures of day 0
+pub fn solutions() -> impl Iterator<Item = &'static Solution> {
+    SOLUTIONS.iter().chain(FIXTURES)
+}
+
+/// Days that have at least one solution, in order
+pub fn days() -> Vec<u32> {
+    let mut days = SOLUTIONS.iter().map(|s| s.day).collect::<Vec<_>>();
+    days.dedup();
+    days
+}
+
+/// All variants of a part, the unnamed one first
+pub fn variants(day: u32, part: u32) -> impl Iterator<Item = &'static Solution> {
+    solutions().filter(move |s| s.day == day && s.part == part)
+}
+
+/// A single solution, `None` as the variant selects the unnamed one
+pub fn find(day: u32, part: u32, variant: Option<&str>) -> Option<&'static Solution> {
+    variants(day, part).find(|s| s.variant == variant)
+}
+
+/// Like [`find`], but [`DEFAULT`] also names the unnamed variant and an unknown name is an error
+/// listing the available ones
+pub fn select(
+    day: u32,
+    part: u32,
+    variant: Option<&str>,
+) -> Result<&'static Solution, Box<dyn Error>> {
+    let variant = variant.filter(|v| *v != DEFAULT);
+    if let Some(s) = find(day, part, variant) {
+        return Ok(s);
+    }
+
+    let names = variants(day, part)
+        .map(|s| s.variant.unwrap_or(DEFAULT))
+        .collect::<Vec<_>>();
+    if names.is_empty() {
+        return Err(format!("Day {day} part {part} has no solution").into());
+    }
+    Err(format!(
+        "Day {day} part {part} has no variant {:?}, available: {}",
+        variant.unwrap_or(DEFAULT),
+        names.join(", ")
+    )
+    .into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solutions_are_sorted() {
+        assert!(SOLUTIONS
+            .windows(2)
+            .all(|w| (w[0].day, w[0].part, w[0].variant) < (w[1].day, w[1].part, w[1].variant)));
+        assert_eq!(days(), (1..=17).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn solve_example() {
+        let answer = find(9, 1, None)
+            .unwrap()
+            .solve("0 3 6 9 12 15\n1 3 6 10 15 21\n10 13 16 21 30 45")
+            .unwrap();
+        assert_eq!(answer.answer, "114");
+        assert_eq!(
+            find(12, 1, Some("regex")).unwrap().to_string(),
+            "Day 12 - Part 1 - regex"
+        );
+        assert!(find(12, 2, Some("regex")).is_none());
+    }
+
+    #[test]
+    fn solve_borrowed() {
+        let day8 = find(8, 1, None).unwrap();
+        assert!(matches!(day8.make, Make::Borrowed(_)));
+        let answer = day8
+            .solve("LLR\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)")
+            .unwrap();
+        assert_eq!(answer.answer, "6");
+        assert_eq!(
+            day8.solve("LXR\n").unwrap_err().to_string(),
+            "Unknown instruction X"
+        );
+    }
+
+    #[test]
+    fn select_by_name() {
+        assert_eq!(select(12, 1, Some("regex")).unwrap().variant, Some("regex"));
+        assert_eq!(select(12, 1, Some(DEFAULT)).unwrap().variant, None);
+        assert_eq!(
+            select(12, 1, Some("fast")).unwrap_err().to_string(),
+            "Day 12 part 1 has no variant \"fast\", available: default, regex"
+        );
+        assert_eq!(
+            select(30, 1, None).unwrap_err().to_string(),
+            "Day 30 part 1 has no solution"
+        );
+    }
+}
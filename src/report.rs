@@ -0,0 +1,286 @@
+//! Markdown table of every day's results for the README.
+//!
+//! The table goes between the [`BEGIN`] and [`END`] markers of the file, the rest of the file is
+//! left untouched. A file without the markers gets the section appended.
+
+use std::{error::Error, fmt::Write, fs, io::ErrorKind, path::Path, sync::Arc, time::Duration};
+
+use crate::{
+    config::Config,
+    memory,
+    registry::{self, Solution},
+    sandbox::{self, Outcome, Sandbox},
+    submit::History,
+};
+
+pub const BEGIN: &str = "<!-- report:begin -->";
+pub const END: &str = "<!-- report:end -->";
+
+const TITLES: [&str; 17] = [
+    "Trebuchet?!",
+    "Cube Conundrum",
+    "Gear Ratios",
+    "Scratchcards",
+    "If You Give A Seed A Fertilizer",
+    "Wait For It",
+    "Camel Cards",
+    "Haunted Wasteland",
+    "Mirage Maintenance",
+    "Pipe Maze",
+    "Cosmic Expansion",
+    "Hot Springs",
+    "Point of Incidence",
+    "Parabolic Reflector Dish",
+    "Lens Library",
+    "The Floor Will Be Lava",
+    "Clumsy Crucible",
+];
+
+/// Puzzle title of a day
+pub fn title(day: u32) -> Option<&'static str> {
+    TITLES.get(day.checked_sub(1)? as usize).copied()
+}
+
+/// How the default variant of a part did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Status {
+    /// The site accepted this answer
+    Correct,
+    /// The site accepted a different answer
+    Wrong(String),
+    /// No accepted answer in the submission history yet
+    Unverified(String),
+    /// `error`, `panic` or `timeout`
+    Failed(&'static str),
+}
+
+impl Status {
+    fn new(outcome: &Outcome, accepted: Option<&str>) -> Self {
+        match (outcome, accepted) {
+            (Outcome::Solved(a), Some(acc)) if a.answer == acc => Self::Correct,
+            (Outcome::Solved(a), Some(_)) => Self::Wrong(a.answer.clone()),
+            (Outcome::Solved(a), None) => Self::Unverified(a.answer.clone()),
+            (Outcome::Failed(_), _) => Self::Failed("error"),
+            (Outcome::Panicked(_), _) => Self::Failed("panic"),
+            (Outcome::TimedOut(_), _) => Self::Failed("timeout"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PartReport {
+    pub part: u32,
+    pub status: Status,
+    /// The quickest variant giving the same answer as the default one, generator included
+    pub fastest: Option<(&'static Solution, Duration)>,
+}
+
+#[derive(Debug)]
+pub struct DayReport {
+    pub day: u32,
+    /// `None` without a cached input
+    pub parts: Option<Vec<PartReport>>,
+    /// Most heap in use at once by any of the day's variants, if measured. Runs that timed out
+    /// or started while a timed out solver was still running (and allocating) are left out.
+    pub peak: Option<usize>,
+}
+
+/// Runs every variant of the day on its input, each in the sandbox. The status of a part is the
+/// one of its variant configured in `config`.
+pub fn run_day(
+    day: u32,
+    input: Option<Arc<str>>,
+    config: &Config,
+    history: &History,
+    sandbox: &Sandbox,
+) -> DayReport {
+    let Some(input) = input else {
+        return DayReport {
+            day,
+            parts: None,
+            peak: None,
+        };
+    };
+
+    let mut peak = None;
+    let mut parts = vec![];
+    for part in [1, 2] {
+        let results = registry::variants(day, part)
+            .map(|s| {
+                let alone = sandbox::running() == 0;
+                let (outcome, used) = memory::measure(|| sandbox.run(s, input.clone()));
+                if alone && !matches!(outcome, Outcome::TimedOut(_)) {
+                    peak = peak.max(used);
+                }
+                (s, outcome)
+            })
+            .collect::<Vec<_>>();
+        let Ok(default) = config.solution(day, part, None) else {
+            continue;
+        };
+        let Some((_, default)) = results.iter().find(|(s, _)| s.variant == default.variant) else {
+            continue;
+        };
+
+        let fastest = results
+            .iter()
+            .filter_map(|(s, o)| match o {
+                Outcome::Solved(a) if Some(a.answer.as_str()) == default.answer() => {
+                    Some((*s, a.generator + a.runner))
+                }
+                _ => None,
+            })
+            .min_by_key(|(_, t)| *t);
+        parts.push(PartReport {
+            part,
+            status: Status::new(default, history.accepted(day, part)),
+            fastest,
+        });
+    }
+
+    DayReport {
+        day,
+        parts: Some(parts),
+        peak,
+    }
+}
+
+/// One row per day
+pub fn render(days: &[DayReport]) -> String {
+    let mut out = String::new();
+    writeln!(
+        out,
+        "| Day | Title | Part 1 | Part 2 | Fastest part 1 | Fastest part 2 | Peak memory |"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "|----:|-------|:------:|:------:|----------------|----------------|------------:|"
+    )
+    .unwrap();
+
+    for d in days {
+        let part = |p: u32| d.parts.as_ref()?.iter().find(|r| r.part == p);
+        let status = |p| match (&d.parts, part(p)) {
+            (None, _) => "no input".to_owned(),
+            (Some(_), None) => "-".to_owned(),
+            (Some(_), Some(r)) => match &r.status {
+                Status::Correct => "⭐".to_owned(),
+                Status::Wrong(a) => format!("❌ `{a}`"),
+                Status::Unverified(a) => format!("`{a}`"),
+                Status::Failed(e) => (*e).to_owned(),
+            },
+        };
+        let fastest = |p| match part(p).and_then(|r| r.fastest) {
+            Some((s, t)) => format!("{} ({t:.2?})", s.variant.unwrap_or(registry::DEFAULT)),
+            None => "-".to_owned(),
+        };
+        let peak = d.peak.map_or_else(|| "-".to_owned(), memory::format_bytes);
+
+        writeln!(
+            out,
+            "| {} | {} | {} | {} | {} | {} | {peak} |",
+            d.day,
+            title(d.day).unwrap_or("?"),
+            status(1),
+            status(2),
+            fastest(1),
+            fastest(2),
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+/// Replaces whatever is between the markers of `text` with `table`
+pub fn splice(text: &str, table: &str) -> String {
+    let section = format!("{BEGIN}\n{table}{END}");
+    let found = text
+        .find(BEGIN)
+        .and_then(|b| Some((b, b + text[b..].find(END)? + END.len())));
+
+    match found {
+        Some((b, e)) => format!("{}{section}{}", &text[..b], &text[e..]),
+        None if text.is_empty() => format!("{section}\n"),
+        None if text.ends_with('\n') => format!("{text}\n{section}\n"),
+        None => format!("{text}\n\n{section}\n"),
+    }
+}
+
+/// Writes `table` into the marked section of the file at `path`, creating the file if needed
+pub fn update(path: impl AsRef<Path>, table: &str) -> Result<(), Box<dyn Error>> {
+    let path = path.as_ref();
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    fs::write(path, splice(&text, table))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::temp_dir;
+
+    #[test]
+    fn splices_between_markers() {
+        let table = "| a |\n";
+        let text = format!("# Title\n\n{BEGIN}\nold\n{END}\n\nMore text\n");
+        assert_eq!(
+            splice(&text, table),
+            format!("# Title\n\n{BEGIN}\n| a |\n{END}\n\nMore text\n")
+        );
+        assert_eq!(
+            splice("# Title\n", table),
+            format!("# Title\n\n{BEGIN}\n| a |\n{END}\n")
+        );
+        assert_eq!(splice("", table), format!("{BEGIN}\n| a |\n{END}\n"));
+        // An end marker before the section doesn't count
+        assert_eq!(
+            splice(&format!("{END}\n{BEGIN}\nold\n{END}"), table),
+            format!("{END}\n{BEGIN}\n| a |\n{END}")
+        );
+    }
+
+    #[test]
+    fn reports_a_day() {
+        let path = History::path_for(temp_dir("reports_a_day"), 2023);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            &path,
+            "0\t6\t1\tcorrect\t-\t288\n0\t6\t2\tcorrect\t-\t71504\n",
+        )
+        .unwrap();
+        let history = History::load(path).unwrap();
+        let input = "Time:      7  15   30\nDistance:  9  40  200";
+        let config = Config::default();
+
+        let run = |day, input: Option<&str>| {
+            run_day(
+                day,
+                input.map(Arc::from),
+                &config,
+                &history,
+                &Sandbox::default(),
+            )
+        };
+        let days = [run(6, Some(input)), run(9, Some("1 2 x")), run(10, None)];
+        let day6 = days[0].parts.as_ref().unwrap();
+        assert_eq!(day6[0].status, Status::Correct);
+        assert_eq!(day6[1].status, Status::Wrong("71503".to_owned()));
+        assert!(day6[1].fastest.is_some());
+
+        let table = render(&days);
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 5);
+        assert!(
+            lines[2].starts_with("| 6 | Wait For It | ⭐ | ❌ `71503` |"),
+            "{table}"
+        );
+        assert!(lines[3].starts_with("| 9 | Mirage Maintenance | error | error | - | - |"));
+        assert!(lines[4].starts_with("| 10 | Pipe Maze | no input | no input | - | - | - |"));
+    }
+}
@@ -0,0 +1,218 @@
+//! Runs solvers on their own thread so a deep recursion, a panic or an endless loop only fails
+//! that one part instead of the whole run.
+
+use std::{
+    any::Any,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    parallel,
+    registry::{Answer, Solution},
+};
+
+/// Stack of the solver threads unless configured otherwise
+pub const DEFAULT_STACK_SIZE: usize = 256 * 1024 * 1024;
+
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of solver threads still running, timed out ones included
+pub fn running() -> usize {
+    RUNNING.load(Ordering::Relaxed)
+}
+
+/// Counts a solver thread in [`running`] until dropped
+struct Running;
+
+impl Running {
+    fn start() -> Self {
+        RUNNING.fetch_add(1, Ordering::Relaxed);
+        Self
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        RUNNING.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Sandbox {
+    /// Stack of the solver thread in bytes. The recursive solvers need far more than the default.
+    pub stack_size: usize,
+    /// Wall-clock time a solver may take, generator included
+    pub timeout: Duration,
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self {
+            stack_size: DEFAULT_STACK_SIZE,
+            timeout: Duration::from_secs(60),
+        }
+    }
+}
+
+/// What happened when running one solution on one input
+#[derive(Debug)]
+pub enum Outcome {
+    Solved(Answer),
+    Failed(String),
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+impl Outcome {
+    pub fn answer(&self) -> Option<&str> {
+        match self {
+            Self::Solved(a) => Some(&a.answer),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Solved(a) => write!(f, "{} ({:?})", a.answer, a.generator + a.runner),
+            Self::Failed(e) => write!(f, "ERROR: {e}"),
+            Self::Panicked(e) => write!(f, "PANIC: {e}"),
+            Self::TimedOut(t) => write!(f, "TIMEOUT: no answer after {t:?}"),
+        }
+    }
+}
+
+impl Sandbox {
+    /// Solves `input` on a fresh thread within the limits
+    pub fn run(&self, solution: &'static Solution, input: Arc<str>) -> Outcome {
+        self.run_holding(solution, input, ())
+    }
+
+    /// Like [`Sandbox::run`], with `guard` dropped only when the solver thread finishes, which
+    /// is after the timeout if it times out
+    pub fn run_holding<G: Send + 'static>(
+        &self,
+        solution: &'static Solution,
+        input: Arc<str>,
+        guard: G,
+    ) -> Outcome {
+        let res = self.call(solution.to_string(), move || {
+            let _guard = guard;
+            solution.solve(&input).map_err(|e| e.to_string())
+        });
+        match res {
+            Ok(Ok(a)) => Outcome::Solved(a),
+            Ok(Err(e)) => Outcome::Failed(e),
+            Err(o) => o,
+        }
+    }
+
+    /// Runs `f` on a fresh thread named `name`. Panics and timeouts come back as the `Err` outcome.
+    ///
+    /// A timed out thread can't be stopped, it keeps running in the background until it finishes
+    /// or the process exits.
+    pub fn call<R: Send + 'static>(
+        &self,
+        name: String,
+        f: impl FnOnce() -> R + Send + 'static,
+    ) -> Result<R, Outcome> {
+        let (tx, rx) = mpsc::channel();
+        let stack_size = self.stack_size;
+        let running = Running::start();
+        thread::Builder::new()
+            .name(name)
+            .stack_size(stack_size)
+            .spawn(move || {
+                let _running = running;
+                // Workers of `parallel::map` run parts of the solver, so they need the same stack
+                parallel::set_stack_size(stack_size);
+                let _ = tx.send(panic::catch_unwind(AssertUnwindSafe(f)));
+            })
+            .map_err(|e| Outcome::Failed(format!("Could not start the solver thread: {e}")))?;
+
+        match rx.recv_timeout(self.timeout) {
+            Ok(Ok(r)) => Ok(r),
+            Ok(Err(payload)) => Err(Outcome::Panicked(panic_message(payload.as_ref()))),
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(Outcome::TimedOut(self.timeout)),
+            Err(mpsc::RecvTimeoutError::Disconnected) => {
+                Err(Outcome::Failed("The solver thread died".to_owned()))
+            }
+        }
+    }
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry;
+
+    fn depth(n: u64) -> u64 {
+        // Keep a buffer alive across the call so every frame takes real stack space
+        let buf = std::hint::black_box([n; 16]);
+        if n == 0 {
+            0
+        } else {
+            depth(n - 1) + buf[0] / n
+        }
+    }
+
+    #[test]
+    fn deep_recursion() {
+        let sandbox = Sandbox::default();
+        let res = sandbox.call("deep".into(), || depth(500_000));
+        assert!(matches!(res, Ok(500_000)));
+    }
+
+    #[test]
+    fn deep_recursion_on_workers() {
+        // Results don't depend on the thread count, so other tests don't mind
+        parallel::set_threads(2);
+        let sandbox = Sandbox::default();
+        let res = sandbox.call("deep workers".into(), || {
+            parallel::map(&[400_000, 500_000], |&n| depth(n))
+        });
+        parallel::set_threads(0);
+        assert!(matches!(res, Ok(v) if v == [400_000, 500_000]));
+    }
+
+    #[test]
+    fn captures_panics_and_timeouts() {
+        let sandbox = Sandbox {
+            timeout: Duration::from_millis(100),
+            ..Default::default()
+        };
+
+        let res = sandbox.call("panic".into(), || panic!("boom"));
+        assert!(matches!(res, Err(Outcome::Panicked(m)) if m == "boom"));
+
+        let res = sandbox.call("slow".into(), || thread::sleep(Duration::from_secs(5)));
+        assert!(matches!(res, Err(Outcome::TimedOut(_))));
+    }
+
+    #[test]
+    fn run_solution() {
+        let sandbox = Sandbox::default();
+        let day9 = registry::find(9, 1, None).unwrap();
+
+        let out = sandbox.run(day9, "0 3 6 9 12 15".into());
+        assert_eq!(out.answer(), Some("18"));
+        let out = sandbox.run(day9, "1 2 x".into());
+        assert!(matches!(out, Outcome::Failed(_)), "{out}");
+    }
+}
@@ -0,0 +1,203 @@
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+
+const TEMPLATE: &str = r####"use aoc_runner_derive::{aoc, aoc_generator};
+
+type ParsedInput = Vec<String>;
+
+#[aoc_generator(day{{day}})]
+fn parse(input: &str) -> ParsedInput {
+    input.lines().map(String::from).collect::<Vec<_>>()
+}
+
+#[aoc(day{{day}}, part1)]
+fn part1(_input: &ParsedInput) -> i64 {
+    todo!()
+}
+
+#[aoc(day{{day}}, part2)]
+fn part2(_input: &ParsedInput) -> i64 {
+    todo!()
+}
+
+#[cfg(test)]
+mod tests {
+    const TESTCASE: &str = r#""#;
+
+    examples! {
+        TESTCASE => part1: 0, part2: 0;
+    }
+}
+"####;
+
+/// Source of a fresh day module
+pub fn render(day: u32) -> String {
+    TEMPLATE.replace("{{day}}", &day.to_string())
+}
+
+/// Creates `src/dayN.rs` under `root` and registers it in `src/lib.rs` and `src/registry.rs`.
+/// Refuses to touch a day that already exists.
+pub fn scaffold(root: impl AsRef<Path>, day: u32) -> Result<PathBuf, Box<dyn Error>> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("Invalid day: {day}").into());
+    }
+
+    let src = root.as_ref().join("src");
+    let module = src.join(format!("day{day}.rs"));
+    if module.exists() {
+        return Err(format!("{} already exists", module.display()).into());
+    }
+
+    let lib_path = src.join("lib.rs");
+    let lib = register_module(&fs::read_to_string(&lib_path)?, day)?;
+    let registry_path = src.join("registry.rs");
+    let registry = register_solutions(&fs::read_to_string(&registry_path)?, day)?;
+
+    fs::write(&module, render(day))?;
+    fs::write(&lib_path, lib)?;
+    fs::write(&registry_path, registry)?;
+
+    Ok(module)
+}
+
+/// Adds `mod dayN;` to the module list in `lib`, keeping the days sorted
+fn register_module(lib: &str, day: u32) -> Result<String, Box<dyn Error>> {
+    let re = Regex::new(r"^mod day(\d+);$").unwrap();
+    insert_sorted(lib, &re, day, &[format!("mod day{day};")], "aoc_lib!")
+        .ok_or_else(|| "Could not find where to register the module in lib.rs".into())
+}
+
+/// Adds both parts of the day to the `SOLUTIONS` list in `registry`
+fn register_solutions(registry: &str, day: u32) -> Result<String, Box<dyn Error>> {
+    let re = Regex::new(r"^Solution::new\((\d+),").unwrap();
+    let entries =
+        [1, 2].map(|p| format!("    Solution::new({day}, {p}, None, Factory::day{day}_part{p}),"));
+    insert_sorted(registry, &re, day, &entries, "];")
+        .ok_or_else(|| "Could not find where to register the solutions in registry.rs".into())
+}
+
+/// Inserts `new` after the last line whose day (first capture of `re`) is smaller than `day`.
+/// Without such a line, it goes before the first matching line, or before the `fallback` line.
+/// Returns `None` if the day is already present or there is nowhere to insert.
+fn insert_sorted(
+    src: &str,
+    re: &Regex,
+    day: u32,
+    new: &[String],
+    fallback: &str,
+) -> Option<String> {
+    let mut lines = src.lines().collect::<Vec<_>>();
+
+    let mut first = None;
+    let mut after = None;
+    for (i, l) in lines.iter().enumerate() {
+        if let Some(c) = re.captures(l.trim()) {
+            let d = c[1].parse::<u32>().ok()?;
+            if d == day {
+                return None;
+            }
+            first.get_or_insert(i);
+            if d < day {
+                after = Some(i + 1);
+            }
+        }
+    }
+
+    let idx = after
+        .or(first)
+        .or_else(|| lines.iter().position(|l| l.starts_with(fallback)))?;
+    for (i, l) in new.iter().enumerate() {
+        lines.insert(idx + i, l);
+    }
+
+    Some(lines.join("\n") + "\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_server::temp_dir;
+
+    const LIB: &str = "use aoc_runner_derive::aoc_lib;
+
+mod day2;
+mod day9;
+
+mod day10;
+
+aoc_lib! { year = 2023 }
+";
+
+    const REGISTRY: &str = "pub static SOLUTIONS: &[Solution] = &[
+    Solution::new(2, 1, None, Factory::day2_part1),
+    Solution::new(2, 2, Some(\"fast\"), Factory::day2_part2_fast),
+];
+";
+
+    #[test]
+    fn register_module_keeps_order() {
+        assert_eq!(
+            register_module(LIB, 1).unwrap(),
+            LIB.replace("mod day2;", "mod day1;\nmod day2;")
+        );
+        assert_eq!(
+            register_module(LIB, 5).unwrap(),
+            LIB.replace("mod day2;", "mod day2;\nmod day5;")
+        );
+        assert_eq!(
+            register_module(LIB, 11).unwrap(),
+            LIB.replace("mod day10;", "mod day10;\nmod day11;")
+        );
+        assert!(register_module(LIB, 9).is_err());
+    }
+
+    #[test]
+    fn register_solutions_keeps_order() {
+        let res = register_solutions(REGISTRY, 3).unwrap();
+        assert!(res.ends_with(
+            "day2_part2_fast),
+    Solution::new(3, 1, None, Factory::day3_part1),
+    Solution::new(3, 2, None, Factory::day3_part2),
+];
+"
+        ));
+        assert!(register_solutions(REGISTRY, 2).is_err());
+        assert!(
+            register_solutions("pub static SOLUTIONS: &[Solution] = &[\n];\n", 1)
+                .unwrap()
+                .contains("&[\n    Solution::new(1, 1,")
+        );
+    }
+
+    #[test]
+    fn scaffold_creates_module() {
+        let root = temp_dir("scaffold_creates_module");
+        fs::create_dir(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        fs::write(root.join("src/registry.rs"), REGISTRY).unwrap();
+
+        let module = scaffold(&root, 18).unwrap();
+        let src = fs::read_to_string(module).unwrap();
+        assert!(src.contains("#[aoc_generator(day18)]"));
+        assert!(src.contains("#[aoc(day18, part2)]"));
+        assert!(fs::read_to_string(root.join("src/lib.rs"))
+            .unwrap()
+            .contains("mod day10;\nmod day18;\n"));
+        assert!(fs::read_to_string(root.join("src/registry.rs"))
+            .unwrap()
+            .contains("Factory::day18_part2),\n];"));
+
+        // Never overwrites an existing day
+        fs::write(root.join("src/day18.rs"), "// solved").unwrap();
+        assert!(scaffold(&root, 18).is_err());
+        assert_eq!(
+            fs::read_to_string(root.join("src/day18.rs")).unwrap(),
+            "// solved"
+        );
+    }
+}
@@ -0,0 +1,303 @@
+//! How the solvers scale: every variant of a day is timed on generated inputs of geometrically
+//! increasing size, and a power law `time ~ n^k` is fitted to find the growth exponent `k`.
+//!
+//! Timings of a debug build say little, run `scaling` in release mode.
+
+use std::{fmt::Write, time::Duration};
+
+use crate::{
+    registry::{self, Solution},
+    sandbox::{Outcome, Sandbox},
+};
+
+/// Generates inputs of a day at a given size
+pub struct Workload {
+    pub day: u32,
+    /// What the size `n` counts
+    pub unit: &'static str,
+    /// Smallest size
+    pub start: usize,
+    pub generate: fn(usize) -> String,
+}
+
+pub const WORKLOADS: &[Workload] = &[
+    Workload {
+        day: 6,
+        unit: "race time",
+        start: 1 << 12,
+        generate: day6,
+    },
+    Workload {
+        day: 9,
+        unit: "values per history",
+        start: 64,
+        generate: day9,
+    },
+    Workload {
+        day: 11,
+        unit: "galaxies",
+        start: 64,
+        generate: day11,
+    },
+    Workload {
+        day: 13,
+        unit: "cells per pattern",
+        start: 64,
+        generate: day13,
+    },
+];
+
+/// splitmix64 of `i`, so the generated inputs are the same on every run
+fn noise(i: usize) -> u64 {
+    let mut z = (i as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// A single race of length `n` that can be won
+fn day6(n: usize) -> String {
+    format!("Time: {n}\nDistance: {}", n * n / 5)
+}
+
+/// Ten cubic histories of `n` values each
+fn day9(n: usize) -> String {
+    (0..10)
+        .map(|l| {
+            (0..n as i64)
+                .map(|i| (i * i * i - l * i * i + 3).to_string())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A square image with `n` galaxies on average, one in eight cells
+fn day11(n: usize) -> String {
+    let side = ((n * 8) as f64).sqrt().ceil() as usize;
+    (0..side)
+        .map(|y| {
+            (0..side)
+                .map(|x| match noise(y * side + x) % 8 {
+                    0 => '#',
+                    _ => '.',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A single square pattern of `n` cells
+fn day13(n: usize) -> String {
+    let side = (n as f64).sqrt().round().max(2.0) as usize;
+    (0..side)
+        .map(|y| {
+            (0..side)
+                .map(|x| match noise(y * side + x) % 2 {
+                    0 => '#',
+                    _ => '.',
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Number of sizes, each `factor` times the previous one
+    pub steps: u32,
+    pub factor: usize,
+    /// Runs per size, the fastest one counts
+    pub repeats: u32,
+    /// A variant that takes longer than this on a size isn't run on the bigger ones
+    pub max_time: Duration,
+    /// Faster runs are mostly noise and are left out of the fit
+    pub min_time: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            steps: 8,
+            factor: 2,
+            repeats: 3,
+            max_time: Duration::from_secs(2),
+            min_time: Duration::from_micros(50),
+        }
+    }
+}
+
+/// Timings of one variant
+#[derive(Debug)]
+pub struct Curve {
+    pub solution: &'static Solution,
+    pub unit: &'static str,
+    /// Size and fastest time
+    pub points: Vec<(usize, Duration)>,
+    /// Why the sizes stopped early, if a run did not produce an answer
+    pub error: Option<String>,
+    min_time: Duration,
+}
+
+impl Curve {
+    /// Growth exponent fitted to the points that took at least the minimum time, `None` with
+    /// fewer than three of them
+    pub fn exponent(&self) -> Option<f64> {
+        let points = self
+            .points
+            .iter()
+            .filter(|(_, t)| *t >= self.min_time)
+            .map(|&(n, t)| (n as f64, t.as_secs_f64()))
+            .collect::<Vec<_>>();
+        if points.len() < 3 {
+            return None;
+        }
+        fit(&points)
+    }
+}
+
+/// Slope of the least squares line through the points on a log-log scale
+pub fn fit(points: &[(f64, f64)]) -> Option<f64> {
+    let logs = points
+        .iter()
+        .map(|(x, y)| (x.ln(), y.ln()))
+        .collect::<Vec<_>>();
+    let len = logs.len() as f64;
+    let mean_x = logs.iter().map(|(x, _)| x).sum::<f64>() / len;
+    let mean_y = logs.iter().map(|(_, y)| y).sum::<f64>() / len;
+    let cov = logs
+        .iter()
+        .map(|(x, y)| (x - mean_x) * (y - mean_y))
+        .sum::<f64>();
+    let var = logs.iter().map(|(x, _)| (x - mean_x).powi(2)).sum::<f64>();
+    (var > 0.0).then(|| cov / var)
+}
+
+/// Times every variant of the workload's day, each run in the sandbox
+pub fn measure(workload: &Workload, options: &Options, sandbox: &Sandbox) -> Vec<Curve> {
+    let sizes = (0..options.steps)
+        .map(|i| workload.start * options.factor.pow(i))
+        .collect::<Vec<_>>();
+    let inputs = sizes
+        .iter()
+        .map(|&n| (workload.generate)(n))
+        .collect::<Vec<_>>();
+
+    let mut curves = vec![];
+    for part in [1, 2] {
+        for solution in registry::variants(workload.day, part) {
+            let mut curve = Curve {
+                solution,
+                unit: workload.unit,
+                points: vec![],
+                error: None,
+                min_time: options.min_time,
+            };
+            'sizes: for (&n, input) in sizes.iter().zip(&inputs) {
+                let mut best = Duration::MAX;
+                for _ in 0..options.repeats {
+                    match sandbox.run(solution, input.as_str().into()) {
+                        Outcome::Solved(a) => best = best.min(a.generator + a.runner),
+                        o => {
+                            curve.error = Some(format!("n = {n}: {o}"));
+                            break 'sizes;
+                        }
+                    }
+                }
+                curve.points.push((n, best));
+                if best > options.max_time {
+                    break;
+                }
+            }
+            curves.push(curve);
+        }
+    }
+
+    curves
+}
+
+/// One line per variant, with the ones above `max_exponent` flagged
+pub fn render(curves: &[Curve], max_exponent: f64) -> String {
+    let mut out = String::new();
+    for c in curves {
+        let (Some(&(first, _)), Some(&(last, t))) = (c.points.first(), c.points.last()) else {
+            writeln!(out, "{}: no timings", c.solution).unwrap();
+            continue;
+        };
+        let range = format!("{} = {first}..{last} ({t:.2?} at {last})", c.unit);
+        match c.exponent() {
+            Some(k) if k > max_exponent => writeln!(
+                out,
+                "!! {}: n^{k:.2} over {range}, above n^{max_exponent}",
+                c.solution
+            ),
+            Some(k) => writeln!(out, "{}: n^{k:.2} over {range}", c.solution),
+            None => writeln!(out, "{}: too fast to fit over {range}", c.solution),
+        }
+        .unwrap();
+        if let Some(e) = &c.error {
+            writeln!(out, "   stopped at {e}").unwrap();
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fits_power_laws() {
+        let points = |k: f64| {
+            (1..6)
+                .map(|i| {
+                    let n = f64::from(1 << i);
+                    (n, 3e-6 * n.powf(k))
+                })
+                .collect::<Vec<_>>()
+        };
+        for k in [0.0, 1.0, 2.0, 2.5] {
+            assert!((fit(&points(k)).unwrap() - k).abs() < 1e-9);
+        }
+        assert_eq!(fit(&[(4.0, 1.0), (4.0, 2.0)]), None);
+    }
+
+    #[test]
+    fn workloads_are_valid_inputs() {
+        let options = Options {
+            steps: 2,
+            repeats: 1,
+            ..Default::default()
+        };
+        for w in WORKLOADS {
+            let curves = measure(w, &options, &Sandbox::default());
+            assert!(!curves.is_empty());
+            for c in &curves {
+                assert_eq!(c.error, None, "{}", c.solution);
+                assert_eq!(c.points.len(), 2);
+            }
+        }
+    }
+
+    #[test]
+    fn flags_steep_curves() {
+        let solution = registry::find(11, 1, None).unwrap();
+        let curve = Curve {
+            solution,
+            unit: "galaxies",
+            points: (1..5)
+                .map(|i| (1 << i, Duration::from_millis(1 << (2 * i))))
+                .collect(),
+            error: None,
+            min_time: Duration::ZERO,
+        };
+        let out = render(&[curve], 1.5);
+        assert_eq!(
+            out,
+            "!! Day 11 - Part 1: n^2.00 over galaxies = 2..16 (256.00ms at 16), above n^1.5\n"
+        );
+    }
+}
@@ -0,0 +1,384 @@
+//! Tiny HTTP service for solving inputs without a local Rust toolchain.
+//!
+//! - `GET /days` lists the available days, parts and variants
+//! - `POST /2023/{day}/{part}[?variant=name]` solves the request body
+//!
+//! Every connection gets its own thread and every solve runs in the sandbox,
+//! so a slow solver only times out its own request. Both are capped: past
+//! [`Limits::max_connections`] or [`Limits::max_solves`] the server answers 503.
+
+use std::{
+    error::Error,
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use serde_json::{json, Value};
+
+use crate::{
+    registry,
+    sandbox::{Outcome, Sandbox},
+    YEAR,
+};
+
+/// Longest request line or header line we accept
+const MAX_LINE: usize = 8 * 1024;
+const MAX_HEADERS: usize = 64;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Limits {
+    /// Largest accepted request body in bytes
+    pub max_body: usize,
+    /// How long a single solve may take
+    pub timeout: Duration,
+    /// Solver threads running at once. A timed out solver keeps running, and keeps its slot,
+    /// until it finishes.
+    pub max_solves: usize,
+    /// Connections handled at once
+    pub max_connections: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_body: 1024 * 1024,
+            timeout: Duration::from_secs(10),
+            max_solves: 4,
+            max_connections: 64,
+        }
+    }
+}
+
+pub struct Server {
+    listener: TcpListener,
+    limits: Limits,
+    connections: Arc<Slots>,
+    solves: Arc<Slots>,
+}
+
+/// A counting semaphore that never blocks
+struct Slots {
+    used: AtomicUsize,
+    max: usize,
+}
+
+/// Frees its slot when dropped
+struct Slot(Arc<Slots>);
+
+impl Slots {
+    fn new(max: usize) -> Arc<Self> {
+        Arc::new(Self {
+            used: AtomicUsize::new(0),
+            max,
+        })
+    }
+
+    fn try_acquire(self: &Arc<Self>) -> Option<Slot> {
+        self.used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < self.max).then_some(n + 1)
+            })
+            .ok()
+            .map(|_| Slot(self.clone()))
+    }
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        self.0.used.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: Option<String>,
+    body: String,
+}
+
+struct Response {
+    status: u16,
+    body: Value,
+}
+
+impl Response {
+    fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    fn error(status: u16, msg: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": msg.into() }),
+        }
+    }
+}
+
+impl Server {
+    pub fn bind(addr: impl ToSocketAddrs, limits: Limits) -> io::Result<Self> {
+        Ok(Self {
+            listener: TcpListener::bind(addr)?,
+            limits,
+            connections: Slots::new(limits.max_connections),
+            solves: Slots::new(limits.max_solves),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serves requests forever
+    pub fn serve(self) {
+        for stream in self.listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let Some(slot) = self.connections.try_acquire() else {
+                let resp = Response::error(503, "Too many connections");
+                if let Err(e) = respond(&mut stream, resp) {
+                    eprintln!("Connection error: {e}");
+                }
+                continue;
+            };
+            let limits = self.limits;
+            let solves = self.solves.clone();
+            thread::spawn(move || {
+                let _slot = slot;
+                if let Err(e) = handle(stream, limits, &solves) {
+                    eprintln!("Connection error: {e}");
+                }
+            });
+        }
+    }
+}
+
+fn handle(
+    mut stream: TcpStream,
+    limits: Limits,
+    solves: &Arc<Slots>,
+) -> Result<(), Box<dyn Error>> {
+    // Don't let a client that never finishes its request hold on to a thread
+    stream.set_read_timeout(Some(Duration::from_secs(30)))?;
+
+    let resp = match read_request(&mut stream, limits) {
+        Ok(req) => route(req, limits, solves),
+        Err(resp) => resp,
+    };
+    respond(&mut stream, resp)
+}
+
+fn respond(stream: &mut TcpStream, resp: Response) -> Result<(), Box<dyn Error>> {
+    let body = resp.body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        resp.status,
+        reason(resp.status),
+        body.len()
+    )?;
+    stream.flush()?;
+
+    Ok(())
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        411 => "Length Required",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Unknown",
+    }
+}
+
+fn read_line(reader: &mut impl BufRead) -> Result<String, Response> {
+    let mut line = String::new();
+    reader
+        .take(MAX_LINE as u64)
+        .read_line(&mut line)
+        .map_err(|e| Response::error(400, e.to_string()))?;
+    if !line.ends_with('\n') {
+        return Err(Response::error(431, "Line too long"));
+    }
+    Ok(line.trim_end().to_owned())
+}
+
+fn read_request(stream: &mut TcpStream, limits: Limits) -> Result<Request, Response> {
+    let mut reader = BufReader::new(stream);
+
+    let start = read_line(&mut reader)?;
+    let mut start = start.split_ascii_whitespace();
+    let (Some(method), Some(target)) = (start.next(), start.next()) else {
+        return Err(Response::error(400, "Malformed request line"));
+    };
+    let (path, query) = match target.split_once('?') {
+        Some((p, q)) => (p, Some(q.to_owned())),
+        None => (target, None),
+    };
+
+    let mut len = None;
+    for i in 0.. {
+        if i == MAX_HEADERS {
+            return Err(Response::error(431, "Too many headers"));
+        }
+        let line = read_line(&mut reader)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                len = Some(
+                    value
+                        .trim()
+                        .parse::<usize>()
+                        .map_err(|_| Response::error(400, "Invalid Content-Length"))?,
+                );
+            }
+        }
+    }
+
+    let body = match (method, len) {
+        (_, Some(len)) if len > limits.max_body => {
+            return Err(Response::error(
+                413,
+                format!("Input is larger than {} bytes", limits.max_body),
+            ))
+        }
+        ("POST", None) => return Err(Response::error(411, "Content-Length is required")),
+        (_, Some(len)) => {
+            let mut body = vec![0; len];
+            reader
+                .read_exact(&mut body)
+                .map_err(|e| Response::error(400, e.to_string()))?;
+            String::from_utf8(body).map_err(|_| Response::error(400, "Input is not UTF-8"))?
+        }
+        (_, None) => String::new(),
+    };
+
+    Ok(Request {
+        method: method.to_owned(),
+        path: path.to_owned(),
+        query,
+        body,
+    })
+}
+
+fn route(req: Request, limits: Limits, solves: &Arc<Slots>) -> Response {
+    let segments = req.path.trim_matches('/').split('/').collect::<Vec<_>>();
+    match (req.method.as_str(), segments.as_slice()) {
+        ("GET", ["days"]) => days(),
+        ("POST", [year, day, part]) => {
+            let (Ok(year), Ok(day), Ok(part)) = (year.parse(), day.parse(), part.parse()) else {
+                return Response::error(404, "Not found");
+            };
+            let variant = req.query.as_deref().and_then(|q| {
+                q.split('&')
+                    .find_map(|kv| kv.strip_prefix("variant="))
+                    .map(percent_decode)
+            });
+            match variant.transpose() {
+                Ok(variant) => solve(year, day, part, variant, req.body, limits, solves),
+                Err(resp) => resp,
+            }
+        }
+        (_, ["days"]) | (_, [_, _, _]) => Response::error(405, "Method not allowed"),
+        _ => Response::error(404, "Not found"),
+    }
+}
+
+/// Decodes `%XX` escapes and `+` for spaces, as in form-encoded query strings
+fn percent_decode(s: &str) -> Result<String, Response> {
+    let invalid = || Response::error(400, format!("Invalid query value: {s}"));
+    let mut bytes = vec![];
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        rest = tail;
+        match b {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = rest.get(..2).ok_or_else(invalid)?;
+                // `from_str_radix` would also take a sign, as in `%+1`
+                if !hex.iter().all(u8::is_ascii_hexdigit) {
+                    return Err(invalid());
+                }
+                let hex = std::str::from_utf8(hex).map_err(|_| invalid())?;
+                bytes.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+                rest = &rest[2..];
+            }
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+fn days() -> Response {
+    let days = registry::days()
+        .into_iter()
+        .map(|day| {
+            let parts = [1, 2]
+                .into_iter()
+                .map(|part| {
+                    json!({
+                        "part": part,
+                        "variants": registry::variants(day, part).map(|s| s.variant).collect::<Vec<_>>(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            json!({ "day": day, "parts": parts })
+        })
+        .collect::<Vec<_>>();
+
+    Response::ok(json!({ "year": YEAR, "days": days }))
+}
+
+fn solve(
+    year: u32,
+    day: u32,
+    part: u32,
+    variant: Option<String>,
+    input: String,
+    limits: Limits,
+    solves: &Arc<Slots>,
+) -> Response {
+    if year != YEAR {
+        return Response::error(404, format!("Only {YEAR} is available"));
+    }
+    let solution = match registry::select(day, part, variant.as_deref()) {
+        Ok(s) => s,
+        Err(e) => return Response::error(404, e.to_string()),
+    };
+
+    let Some(slot) = solves.try_acquire() else {
+        return Response::error(503, "Too many solves running, try again later");
+    };
+
+    let sandbox = Sandbox {
+        timeout: limits.timeout,
+        ..Default::default()
+    };
+    // The slot goes with the solver thread, which outlives the request when it times out
+    match sandbox.run_holding(solution, input.into(), slot) {
+        Outcome::Solved(answer) => Response::ok(json!({
+            "day": day,
+            "part": part,
+            "variant": solution.variant,
+            "answer": answer.answer,
+            "generator_ms": answer.generator.as_secs_f64() * 1000.0,
+            "runner_ms": answer.runner.as_secs_f64() * 1000.0,
+        })),
+        Outcome::Failed(e) => Response::error(400, e),
+        Outcome::Panicked(_) => Response::error(500, "Solver panicked"),
+        Outcome::TimedOut(t) => Response::error(504, format!("Solver did not finish in {t:?}")),
+    }
+}
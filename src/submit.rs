@@ -0,0 +1,306 @@
+use std::{
+    error::Error,
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::api::{Client, Submission, Verdict};
+
+/// How many times a rate-limited submission is retried before giving up
+const MAX_ATTEMPTS: usize = 3;
+/// Wait before retrying when the site says we are rate limited but not for how long
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A single submitted answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Seconds since the UNIX epoch
+    pub time: u64,
+    pub day: u32,
+    pub part: u32,
+    pub answer: String,
+    pub verdict: Verdict,
+    pub wait: Option<Duration>,
+}
+
+impl Entry {
+    fn parse(line: &str) -> Result<Self, Box<dyn Error>> {
+        let mut f = line.splitn(6, '\t');
+        let mut next = || f.next().ok_or("Missing field");
+        Ok(Self {
+            time: next()?.parse()?,
+            day: next()?.parse()?,
+            part: next()?.parse()?,
+            verdict: next()?.parse()?,
+            wait: match next()? {
+                "-" => None,
+                w => Some(Duration::from_secs(w.parse()?)),
+            },
+            answer: next()?.to_owned(),
+        })
+    }
+
+    fn to_line(&self) -> String {
+        let wait = self
+            .wait
+            .map_or_else(|| "-".to_owned(), |w| w.as_secs().to_string());
+        format!(
+            "{}\t{}\t{}\t{}\t{wait}\t{}",
+            self.time, self.day, self.part, self.verdict, self.answer
+        )
+    }
+}
+
+/// Every answer ever submitted, stored as a tab-separated file next to the cached inputs
+pub struct History {
+    path: PathBuf,
+    entries: Vec<Entry>,
+}
+
+impl History {
+    /// Default location, e.g. `input/2023/submissions.tsv`
+    pub fn path_for(cache_dir: impl AsRef<Path>, year: u32) -> PathBuf {
+        cache_dir
+            .as_ref()
+            .join(year.to_string())
+            .join("submissions.tsv")
+    }
+
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, Box<dyn Error>> {
+        let path = path.into();
+        let entries = match fs::read_to_string(&path) {
+            Ok(s) => s
+                .lines()
+                .filter(|l| !l.trim().is_empty())
+                .enumerate()
+                .map(|(i, l)| {
+                    Entry::parse(l).map_err(|e| format!("{}:{}: {e}", path.display(), i + 1))
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            Err(e) if e.kind() == ErrorKind::NotFound => vec![],
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Self { path, entries })
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Appends the entry to the history file
+    pub fn record(&mut self, entry: Entry) -> Result<(), Box<dyn Error>> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut f = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(f, "{}", entry.to_line())?;
+        self.entries.push(entry);
+
+        Ok(())
+    }
+
+    /// Fails if the history already tells us what the server would answer
+    pub fn check(&self, day: u32, part: u32, answer: &str) -> Result<(), Box<dyn Error>> {
+        let num = answer.parse::<i64>().ok();
+        for e in &self.entries {
+            if e.day != day || e.part != part || !e.verdict.is_checked() {
+                continue;
+            }
+
+            if e.verdict == Verdict::Correct {
+                return Err(format!(
+                    "Day {day} part {part} is already solved, the answer was {}",
+                    e.answer
+                )
+                .into());
+            }
+            if e.answer == answer {
+                return Err(format!("{answer} was already submitted and was {}", e.verdict).into());
+            }
+
+            let bound = e.answer.parse::<i64>().ok();
+            if let (Some(n), Some(b)) = (num, bound) {
+                match e.verdict {
+                    Verdict::TooHigh if n >= b => {
+                        return Err(format!("{answer} is too high, {b} was already too high").into())
+                    }
+                    Verdict::TooLow if n <= b => {
+                        return Err(format!("{answer} is too low, {b} was already too low").into())
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The answer the site accepted for a part
+    pub fn accepted(&self, day: u32, part: u32) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|e| e.day == day && e.part == part && e.verdict == Verdict::Correct)
+            .map(|e| e.answer.as_str())
+    }
+
+    /// The earliest time the site accepts another answer
+    pub fn next_allowed(&self) -> Option<SystemTime> {
+        self.entries
+            .iter()
+            .filter_map(|e| e.wait.map(|w| UNIX_EPOCH + Duration::from_secs(e.time) + w))
+            .max()
+    }
+}
+
+/// Submits an answer unless the history says it is pointless, waiting out any rate limit first.
+/// Every response is recorded in the history.
+pub fn submit(
+    client: &Client,
+    history: &mut History,
+    year: u32,
+    day: u32,
+    part: u32,
+    answer: &str,
+    mut sleep: impl FnMut(Duration),
+) -> Result<Submission, Box<dyn Error>> {
+    let answer = answer.trim();
+    if answer.is_empty() || answer.contains(char::is_whitespace) {
+        return Err(format!("Invalid answer: {answer:?}").into());
+    }
+    history.check(day, part, answer)?;
+
+    let mut unknown_wait = false;
+    for _ in 0..MAX_ATTEMPTS {
+        let wait = history
+            .next_allowed()
+            .and_then(|t| t.duration_since(SystemTime::now()).ok());
+        if let Some(wait) = wait.or(unknown_wait.then_some(DEFAULT_BACKOFF)) {
+            sleep(wait);
+        }
+
+        let res = client.submit_answer(year, day, part, answer)?;
+        history.record(Entry {
+            time: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            day,
+            part,
+            answer: answer.to_owned(),
+            verdict: res.verdict,
+            wait: res.wait,
+        })?;
+
+        if res.verdict != Verdict::RateLimited {
+            return Ok(res);
+        }
+        unknown_wait = res.wait.is_none();
+    }
+
+    Err(format!("Still rate limited after {MAX_ATTEMPTS} attempts").into())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::test_server::{temp_dir, TestServer};
+
+    fn entry(part: u32, answer: &str, verdict: Verdict) -> Entry {
+        Entry {
+            time: 1701388800,
+            day: 1,
+            part,
+            answer: answer.to_owned(),
+            verdict,
+            wait: Some(Duration::from_secs(60)),
+        }
+    }
+
+    #[test]
+    fn history_roundtrip() {
+        let path = temp_dir("history_roundtrip").join("submissions.tsv");
+        let mut history = History::load(&path).unwrap();
+        history.record(entry(1, "142", Verdict::TooLow)).unwrap();
+        history.record(entry(1, "281", Verdict::Correct)).unwrap();
+
+        let loaded = History::load(&path).unwrap();
+        assert_eq!(loaded.entries(), history.entries());
+    }
+
+    #[test]
+    fn check_known_answers() {
+        let path = temp_dir("check_known_answers").join("submissions.tsv");
+        let mut history = History::load(path).unwrap();
+        history.record(entry(1, "100", Verdict::TooLow)).unwrap();
+        history.record(entry(1, "500", Verdict::TooHigh)).unwrap();
+        history.record(entry(1, "300", Verdict::Incorrect)).unwrap();
+        history
+            .record(entry(1, "250", Verdict::RateLimited))
+            .unwrap();
+
+        assert!(history.check(1, 1, "300").is_err());
+        assert!(history.check(1, 1, "90").is_err());
+        assert!(history.check(1, 1, "600").is_err());
+        assert!(history.check(1, 1, "250").is_ok());
+        assert!(history.check(1, 1, "200").is_ok());
+        assert!(history.check(1, 2, "300").is_ok());
+
+        history.record(entry(1, "200", Verdict::Correct)).unwrap();
+        assert!(history.check(1, 1, "201").is_err());
+    }
+
+    #[test]
+    fn submit_waits_out_rate_limit() {
+        let calls = AtomicUsize::new(0);
+        let server = TestServer::start(move |_| {
+            let msg = match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => "You gave an answer too recently. You have 5s left to wait.",
+                _ => "That's the right answer!",
+            };
+            (200, format!("<article><p>{msg}</p></article>"))
+        });
+        let client = Client::new("s3cret").with_base_url(server.url());
+        let path = temp_dir("submit_waits_out_rate_limit").join("submissions.tsv");
+        let mut history = History::load(&path).unwrap();
+
+        let mut slept = vec![];
+        let res = submit(&client, &mut history, 2023, 4, 1, "13", |d| slept.push(d)).unwrap();
+        assert_eq!(res.verdict, Verdict::Correct);
+        assert_eq!(server.requests().len(), 2);
+        assert_eq!(slept.len(), 1);
+        assert!(slept[0] <= Duration::from_secs(5) && slept[0] >= Duration::from_secs(3));
+
+        let verdicts = History::load(&path)
+            .unwrap()
+            .entries()
+            .iter()
+            .map(|e| e.verdict)
+            .collect::<Vec<_>>();
+        assert_eq!(verdicts, [Verdict::RateLimited, Verdict::Correct]);
+
+        // Known answer is not sent again
+        assert!(submit(&client, &mut history, 2023, 4, 1, "13", |_| ()).is_err());
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[test]
+    fn submit_backs_off_without_wait_time() {
+        let server = TestServer::start(|_| {
+            let msg = "You gave an answer too recently.";
+            (200, format!("<article><p>{msg}</p></article>"))
+        });
+        let client = Client::new("s3cret").with_base_url(server.url());
+        let path = temp_dir("submit_backs_off_without_wait_time").join("submissions.tsv");
+        let mut history = History::load(path).unwrap();
+
+        let mut slept = vec![];
+        assert!(submit(&client, &mut history, 2023, 4, 1, "13", |d| slept.push(d)).is_err());
+        assert_eq!(server.requests().len(), MAX_ATTEMPTS);
+        assert_eq!(slept, [DEFAULT_BACKOFF; MAX_ATTEMPTS - 1]);
+    }
+}
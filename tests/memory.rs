@@ -0,0 +1,53 @@
+//! Peak memory measurements, in their own test binary because they need `PeakAlloc` as the
+//! global allocator.
+
+use std::{sync::Arc, thread, time::Duration};
+
+use advent_of_code_2023::{
+    config::Config,
+    memory::{measure, PeakAlloc},
+    report, sandbox,
+    sandbox::Sandbox,
+    submit::History,
+};
+
+#[global_allocator]
+static ALLOC: PeakAlloc = PeakAlloc;
+
+#[test]
+fn measures_peak() {
+    let (len, peak) = measure(|| {
+        let v = vec![1u8; 4 << 20];
+        drop(std::hint::black_box(v));
+        3
+    });
+    assert_eq!(len, 3);
+    assert!(peak.unwrap() >= 4 << 20);
+}
+
+#[test]
+fn leaves_out_timed_out_runs() {
+    let history = History::load(std::env::temp_dir().join("aoc2023-no-such-history.tsv")).unwrap();
+    let config = Config::default();
+    let run = |day, input: &str, timeout| {
+        let sandbox = Sandbox {
+            timeout,
+            ..Default::default()
+        };
+        report::run_day(day, Some(Arc::from(input)), &config, &history, &sandbox).peak
+    };
+
+    assert!(run(9, "0 3 6 9 12 15", Duration::from_secs(10)).is_some());
+
+    // Splitters everywhere keep day 16 busy far longer than the timeout
+    let field = vec!["|-".repeat(50); 100].join("\n");
+    assert_eq!(run(16, &field, Duration::from_millis(1)), None);
+    // The timed out solvers are still running and allocating
+    assert!(sandbox::running() > 0);
+    assert_eq!(run(9, "0 3 6 9 12 15", Duration::from_secs(10)), None);
+
+    while sandbox::running() > 0 {
+        thread::sleep(Duration::from_millis(50));
+    }
+    assert!(run(9, "0 3 6 9 12 15", Duration::from_secs(10)).is_some());
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "server")]
+
+use std::{thread, time::Duration};
+
+use advent_of_code_2023::server::{Limits, Server};
+use serde_json::Value;
+
+fn start(limits: Limits) -> String {
+    let server = Server::bind("127.0.0.1:0", limits).unwrap();
+    let url = format!("http://{}", server.local_addr().unwrap());
+    thread::spawn(move || server.serve());
+    url
+}
+
+/// Status and JSON body, including error responses
+fn call(req: ureq::Request, body: Option<&str>) -> (u16, Value) {
+    let res = match body {
+        Some(b) => req.send_string(b),
+        None => req.call(),
+    };
+    let resp = match res {
+        Ok(r) => r,
+        Err(ureq::Error::Status(_, r)) => r,
+        Err(e) => panic!("{e}"),
+    };
+    (resp.status(), resp.into_json().unwrap())
+}
+
+const DAY9: &str = "0 3 6 9 12 15
+1 3 6 10 15 21
+10 13 16 21 30 45";
+
+#[test]
+fn lists_days() {
+    let url = start(Limits::default());
+    let (status, body) = call(ureq::get(&format!("{url}/days")), None);
+
+    assert_eq!(status, 200);
+    assert_eq!(body["year"], 2023);
+    assert_eq!(body["days"][0]["day"], 1);
+    let day12 = &body["days"][11];
+    assert_eq!(day12["day"], 12);
+    assert_eq!(
+        day12["parts"][0]["variants"],
+        serde_json::json!([null, "regex"])
+    );
+}
+
+#[test]
+fn solves_input() {
+    let url = start(Limits::default());
+
+    let (status, body) = call(ureq::post(&format!("{url}/2023/9/1")), Some(DAY9));
+    assert_eq!(status, 200, "{body}");
+    assert_eq!(body["answer"], "114");
+    assert!(body["generator_ms"].is_number());
+    assert!(body["runner_ms"].is_number());
+
+    let (status, body) = call(
+        ureq::post(&format!("{url}/2023/12/1?variant=regex")),
+        Some("???.### 1,1,3"),
+    );
+    assert_eq!(status, 200, "{body}");
+    assert_eq!(body["variant"], "regex");
+    assert_eq!(body["answer"], "1");
+}
+
+#[test]
+fn rejects_bad_requests() {
+    let url = start(Limits {
+        max_body: 1024,
+        ..Limits::default()
+    });
+
+    let (status, _) = call(ureq::post(&format!("{url}/2023/26/1")), Some(DAY9));
+    assert_eq!(status, 404);
+    let (status, _) = call(ureq::post(&format!("{url}/2022/9/1")), Some(DAY9));
+    assert_eq!(status, 404);
+    let (status, _) = call(ureq::get(&format!("{url}/2023/9/1")), None);
+    assert_eq!(status, 405);
+
+    let (status, body) = call(
+        ureq::post(&format!("{url}/2023/9/1")),
+        Some(&"1 2 3\n".repeat(400)),
+    );
+    assert_eq!(status, 413, "{body}");
+}
+
+#[test]
+fn reports_errors_and_timeouts() {
+    let url = start(Limits {
+        timeout: Duration::from_millis(50),
+        ..Limits::default()
+    });
+
+    // Malformed input is the client's fault
+    let (status, body) = call(ureq::post(&format!("{url}/2023/9/1")), Some("1 2 x"));
+    assert_eq!(status, 400, "{body}");
+
+    // A big open field makes day16 part2 take far longer than the timeout
+    let field = vec![".".repeat(200); 200].join("\n");
+    let (status, body) = call(ureq::post(&format!("{url}/2023/16/2")), Some(&field));
+    assert_eq!(status, 504, "{body}");
+}
+
+#[test]
+fn decodes_variant() {
+    let url = start(Limits::default());
+
+    let (status, body) = call(
+        ureq::post(&format!("{url}/2023/12/1?variant=reg%65x")),
+        Some("???.### 1,1,3"),
+    );
+    assert_eq!(status, 200, "{body}");
+    assert_eq!(body["variant"], "regex");
+
+    for variant in ["%zz", "%+1", "%4"] {
+        let (status, body) = call(
+            ureq::post(&format!("{url}/2023/12/1?variant={variant}")),
+            Some("???.### 1,1,3"),
+        );
+        assert_eq!(status, 400, "{variant}: {body}");
+    }
+}
+
+#[test]
+fn caps_running_solves() {
+    let url = start(Limits {
+        timeout: Duration::from_millis(50),
+        max_solves: 1,
+        ..Limits::default()
+    });
+
+    // Splitters everywhere energize the whole field from every edge, which takes far longer than
+    // the timeout, and the timed out solver keeps the only slot
+    let field = vec!["|-".repeat(100); 200].join("\n");
+    let (status, body) = call(ureq::post(&format!("{url}/2023/16/2")), Some(&field));
+    assert_eq!(status, 504, "{body}");
+
+    let (status, body) = call(ureq::post(&format!("{url}/2023/9/1")), Some(DAY9));
+    assert_eq!(status, 503, "{body}");
+}